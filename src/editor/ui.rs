@@ -8,6 +8,7 @@ use super::util::{
   format_menu_item, get_level_menu_items, get_music_files, get_sprite_table, get_sprite_texture_files,
   load_level_sprite_entries, validate_add_sprite_form, AddSpriteForm,
 };
+use crate::game::attributes::{AttributeError, AttributeRegistry};
 use crate::level::config::{LevelManifest, LevelSpriteEntry};
 use crate::level::load::{LevelLoadComplete, LoadLevel, PreparedLevel};
 use crate::level::save::SaveLevel;
@@ -238,7 +239,11 @@ pub fn show_music_menu(
   }
 }
 
-pub fn show_add_sprite_menu(mut egui_context: ResMut<EguiContext>, mut toolbar_state: ResMut<EditorState>) {
+pub fn show_add_sprite_menu(
+  mut egui_context: ResMut<EguiContext>,
+  mut toolbar_state: ResMut<EditorState>,
+  registry: Res<AttributeRegistry>,
+) {
   if !toolbar_state.level_loaded {
     return;
   }
@@ -277,7 +282,7 @@ pub fn show_add_sprite_menu(mut egui_context: ResMut<EguiContext>, mut toolbar_s
             ui.horizontal(|ui| {
               if ui
                 .add_enabled(
-                  validate_add_sprite_form(&toolbar_state.add_sprite_form),
+                  validate_add_sprite_form(&toolbar_state.add_sprite_form, &registry),
                   egui::Button::new("Add Sprite"),
                 )
                 .clicked()
@@ -319,9 +324,31 @@ pub fn show_add_sprite_menu(mut egui_context: ResMut<EguiContext>, mut toolbar_s
                   toolbar_state.add_sprite_sidebar = AddSpriteSidebarState::Hide;
                 }
 
-                if ui.button("New Attribute").clicked() {
-                  toolbar_state.add_sprite_form.attributes.push(Default::default());
-                }
+                ui.horizontal(|ui| {
+                  if ui.button("New Attribute").clicked() {
+                    toolbar_state.add_sprite_form.attributes.push(Default::default());
+                  }
+
+                  // Known attributes, inserted as a template of their required parameters
+                  let mut picked = None;
+                  egui::ComboBox::from_id_source("Add Registered Attribute")
+                    .selected_text("Add...")
+                    .show_ui(ui, |ui| {
+                      for registration in registry.iter() {
+                        if ui
+                          .selectable_label(false, registration.key)
+                          .on_hover_text(registration.signature())
+                          .clicked()
+                        {
+                          picked = Some(registration.template());
+                        }
+                      }
+                    });
+
+                  if let Some(template) = picked {
+                    toolbar_state.add_sprite_form.attributes.push(template);
+                  }
+                });
 
                 let mut to_delete = Vec::new();
 
@@ -332,6 +359,13 @@ pub fn show_add_sprite_menu(mut egui_context: ResMut<EguiContext>, mut toolbar_s
                       to_delete.push(i);
                     }
                   });
+
+                  if let Err(err) = registry.validate(attr) {
+                    if let AttributeError::Syntax(parse_err) = &err {
+                      ui.monospace(parse_err.annotate(attr));
+                    }
+                    ui.colored_label(egui::Color32::RED, err.to_string());
+                  }
                 }
 
                 for i in to_delete {
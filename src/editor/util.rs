@@ -4,6 +4,7 @@ use std::fs;
 use bevy::math::IVec2;
 
 use super::ui::LevelMenuItem;
+use crate::game::attributes::AttributeRegistry;
 use crate::level::config::LevelSpriteEntry;
 use crate::level::util::{get_level_manifests, get_manifest_by_id, get_map_by_id};
 use crate::level::LevelId;
@@ -69,13 +70,14 @@ pub fn get_sprite_texture_files() -> Vec<String> {
   get_asset_dir_files(SPRITE_TEXTURE_DIR_PATH)
 }
 
-pub fn validate_add_sprite_form(form: &AddSpriteForm) -> bool {
+pub fn validate_add_sprite_form(form: &AddSpriteForm, registry: &AttributeRegistry) -> bool {
   !form.name.is_empty()
     && !form.texture.is_empty()
     && !form.offset[0].is_empty()
     && !form.offset[1].is_empty()
     && form.offset[0].parse::<i32>().is_ok()
     && form.offset[1].parse::<i32>().is_ok()
+    && form.attributes.iter().all(|attr| registry.validate(attr).is_ok())
 }
 
 pub fn load_level_sprite_entries(id: LevelId) -> Option<Vec<LevelSpriteEntry>> {
@@ -0,0 +1,190 @@
+//! Simple AI for enemies (see [crate::game::attributes::Enemy]).
+//!
+//! # Enemy Kinds
+//! Walkers patrol back and forth along the ground, turning at walls and
+//! ledges found with rapier ray casts. Flyers ignore the level, homing towards
+//! the player while they are close and drifting back home otherwise.
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use super::attributes::{Enemy, Player};
+use super::collision_groups::*;
+use super::physics::PhysicsCommands;
+use crate::level::config::SPRITE_SIZE;
+
+/// Distance in tiles from which flyers start chasing the player.
+const FLYER_SIGHT: f32 = 8.0;
+/// Distance in tiles ahead of a walker that walls and ledges are looked for.
+const WALKER_LOOKAHEAD: f32 = 0.55;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EnemyKind {
+  Walker,
+  Flyer,
+}
+
+impl EnemyKind {
+  pub fn from_param(value: &str) -> Option<Self> {
+    match value {
+      "walker" => Some(Self::Walker),
+      "flyer" => Some(Self::Flyer),
+      _ => None,
+    }
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EnemyState {
+  /// Walking back and forth, or for flyers, returning home.
+  Patrol,
+  /// Flying towards the player.
+  Chase,
+  /// Killed by the player, until they respawn.
+  Defeated,
+  /// Returning to where the enemy started, such as after the player respawns.
+  Reset,
+}
+
+/// Moves a flyer towards its target without overshooting it.
+pub fn flyer_step(position: Vec2, target: Vec2, speed: f32, delta_t: f32) -> Vec2 {
+  let to_target = target - position;
+  let step = speed * delta_t;
+  if to_target.length() <= step {
+    target
+  } else {
+    position + to_target.normalize() * step
+  }
+}
+
+/// Returns true if a walker at the position, facing the direction, should
+/// turn around.
+fn walker_should_turn(
+  query_pipeline: &QueryPipeline,
+  collider_set: &QueryPipelineColliderComponentsSet,
+  position: Vec2,
+  direction: f32,
+) -> bool {
+  // Walls and ledges are solids, as found by the player's hover
+  let ahead = Vec2::new(direction, 0.0);
+  let wall_ray = Ray::new(position.into(), ahead.into());
+  let wall = query_pipeline.cast_ray(
+    collider_set,
+    &wall_ray,
+    WALKER_LOOKAHEAD,
+    true,
+    PLAYER_HOVER_GROUP,
+    None,
+  );
+
+  let ledge_ray = Ray::new((position + ahead * WALKER_LOOKAHEAD).into(), (-Vec2::Y).into());
+  let ground = query_pipeline.cast_ray(collider_set, &ledge_ray, 1.0, true, PLAYER_HOVER_GROUP, None);
+
+  wall.is_some() || ground.is_none()
+}
+
+/// Moves enemies by their kind and state.
+pub fn enemy_ai_system(
+  time: Res<Time>,
+  physics_commands: Res<PhysicsCommands>,
+  query_pipeline: Res<QueryPipeline>,
+  collider_query: QueryPipelineColliderComponentsQuery,
+  player: Query<&Transform, With<Player>>,
+  mut enemies: Query<(
+    &mut Enemy,
+    &mut ColliderPositionComponent,
+    &mut ColliderFlagsComponent,
+    &mut Visibility,
+    &mut Sprite,
+  )>,
+) {
+  if physics_commands.paused() {
+    return;
+  }
+
+  let collider_set = QueryPipelineColliderComponentsSet(&collider_query);
+  let player_position = player
+    .get_single()
+    .ok()
+    .map(|trans| trans.translation.truncate() / SPRITE_SIZE as f32);
+  let delta_t = time.delta_seconds();
+
+  enemies.for_each_mut(
+    |(mut enemy, mut collider_position, mut flags, mut visibility, mut sprite)| {
+      let translation = collider_position.0.translation;
+      let position = Vec2::new(translation.x, translation.y);
+
+      let position = match (enemy.state, enemy.kind) {
+        (EnemyState::Defeated, _) => return,
+        (EnemyState::Reset, _) => {
+          enemy.state = EnemyState::Patrol;
+          enemy.direction = 1.0;
+          flags.collision_groups = DEADLY_GROUP;
+          visibility.is_visible = true;
+          enemy.home
+        },
+        (_, EnemyKind::Walker) => {
+          if walker_should_turn(&query_pipeline, &collider_set, position, enemy.direction) {
+            enemy.direction = -enemy.direction;
+          }
+          position + Vec2::new(enemy.direction * enemy.speed * delta_t, 0.0)
+        },
+        (_, EnemyKind::Flyer) => {
+          let chasing = player_position.filter(|target| target.distance(position) <= FLYER_SIGHT);
+          enemy.state = if chasing.is_some() {
+            EnemyState::Chase
+          } else {
+            EnemyState::Patrol
+          };
+
+          let target = chasing.unwrap_or(enemy.home);
+          if target.x != position.x {
+            enemy.direction = (target.x - position.x).signum();
+          }
+          flyer_step(position, target, enemy.speed, delta_t)
+        },
+      };
+
+      sprite.flip_x = enemy.direction < 0.0;
+      collider_position.0 = position.into();
+    },
+  );
+}
+
+/// Simulation steps for enemies. Enemy contacts are resolved in
+/// [EnemySystemSteps::ResolveContacts], before contact damage is dealt by
+/// [crate::game::attributes::on_death_system].
+#[derive(Debug, Hash, PartialEq, Eq, Clone, SystemLabel)]
+pub enum EnemySystemSteps {
+  ResolveContacts,
+}
+
+/// [Plugin] for enemy AI.
+pub struct EnemyAiPlugin;
+
+impl Plugin for EnemyAiPlugin {
+  fn build(&self, app: &mut App) {
+    app.add_system(enemy_ai_system);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use bevy::prelude::*;
+
+  use super::*;
+
+  #[test]
+  fn test_flyer_step() {
+    // Moves at its speed towards the target
+    let step = flyer_step(Vec2::ZERO, Vec2::new(10.0, 0.0), 2.0, 0.5);
+    assert_eq!(step, Vec2::new(1.0, 0.0));
+
+    // Never overshoots
+    let step = flyer_step(Vec2::ZERO, Vec2::new(0.0, 0.5), 2.0, 0.5);
+    assert_eq!(step, Vec2::new(0.0, 0.5));
+
+    assert_eq!(EnemyKind::from_param("flyer"), Some(EnemyKind::Flyer));
+    assert_eq!(EnemyKind::from_param("swimmer"), None);
+  }
+}
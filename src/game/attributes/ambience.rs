@@ -0,0 +1,67 @@
+//! Changes the looping background layer, such as rain or wind, when the player
+//! touches the sprite. `ambience(file)`
+//!
+//! # Usage
+//! `file`: Quoted name of a track in the music directory, e.g.
+//! `ambience("rain.ogg")`, or `ambience("")` to stop the ambience
+//!
+//! Ambience plays on its own channels alongside the music, crossfading between
+//! tracks the same way (see [crate::game::music]).
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use super::give::pickup_collider;
+use super::{Attribute, AttributeError, AttributeParams, ParamKind, ParamSpec};
+use crate::game::collision::{ContactQuery, ContactSubscription, PlayerContacted};
+use crate::game::music::{track_exists, MusicCommands};
+use crate::level::LevelId;
+
+#[derive(Component)]
+pub struct Ambience(pub Option<String>);
+
+impl Attribute for Ambience {
+  const KEY: &'static str = "ambience";
+  const PARAMS: &'static [ParamSpec] = &[ParamSpec::required("file", ParamKind::Str)];
+
+  fn build(
+    commands: &mut Commands,
+    target: Entity,
+    _: LevelId,
+    position: Vec2,
+    params: AttributeParams,
+  ) -> Result<(), AttributeError> {
+    let file = params.str("file")?;
+    let track = if file.is_empty() { None } else { Some(file.to_string()) };
+
+    commands
+      .entity(target)
+      .insert(Ambience(track))
+      .insert(ContactSubscription)
+      .insert_bundle(pickup_collider(position))
+      .insert(ColliderPositionSync::Discrete);
+
+    Ok(())
+  }
+
+  fn validate(params: &AttributeParams) -> Result<(), AttributeError> {
+    let file = params.str("file")?;
+    if !file.is_empty() && !track_exists(file) {
+      return Err(AttributeError::invalid("file", "is not a track in the music directory"));
+    }
+
+    Ok(())
+  }
+}
+
+/// Consumes [PlayerContacted] tags, changing the ambience.
+pub fn on_ambience_system(
+  mut commands: Commands,
+  triggers: ContactQuery<Ambience>,
+  mut music_commands: ResMut<MusicCommands>,
+) {
+  triggers.for_each(|(ent, trigger)| {
+    music_commands.play_ambience(trigger.0.clone());
+    commands.entity(ent).remove::<PlayerContacted>();
+  });
+}
@@ -0,0 +1,162 @@
+//! Launches the player on contact. `bounce(optional force, optional dir)`
+//!
+//! # Usage
+//! `force`: Speed the player is launched at, in tiles per second (default 20)
+//!
+//! `dir`: Direction of the launch (left, right, down, up; default up)
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use super::give::pickup_collider;
+use super::{Attribute, AttributeError, AttributeParams, MovingDirection, ParamSpec, Player};
+use crate::game::collision::{ContactSubscription, PlayerContacted};
+use crate::level::LevelId;
+
+/// Seconds before a pad can launch the player again.
+const BOUNCE_COOLDOWN: f32 = 0.25;
+
+/// Distance in tiles the player can be from the pad and still be on it,
+/// covering contacts reported just before the colliders overlap.
+const CONTACT_TOLERANCE: f32 = 0.05;
+
+#[derive(Component)]
+pub struct Bounce {
+  pub velocity: Vec2,
+  /// Seconds left until the pad can launch the player again.
+  cooldown: f32,
+  /// Whether the player has touched the pad and not left it since.
+  touched: bool,
+}
+
+impl Bounce {
+  pub fn new(velocity: Vec2) -> Self {
+    Self {
+      velocity,
+      cooldown: 0.0,
+      touched: false,
+    }
+  }
+
+  /// Steps the cooldown, returning whether to launch the player. A player who
+  /// touches the pad while it cools down is launched once it can again, as
+  /// long as they are still on it.
+  pub fn step(&mut self, delta: f32, contacted: bool, on_pad: bool) -> bool {
+    self.cooldown = (self.cooldown - delta).max(0.0);
+    self.touched = (self.touched || contacted) && on_pad;
+
+    if self.touched && self.cooldown <= 0.0 {
+      self.cooldown = BOUNCE_COOLDOWN;
+      true
+    } else {
+      false
+    }
+  }
+}
+
+/// Whether the player's collider overlaps a pad's tile, both in tiles.
+fn on_pad(pad: Vec2, player: Vec2) -> bool {
+  let closest = player.clamp(pad - Vec2::splat(0.5), pad + Vec2::splat(0.5));
+  closest.distance(player) < 0.5 + CONTACT_TOLERANCE
+}
+
+impl Attribute for Bounce {
+  const KEY: &'static str = "bounce";
+  const PARAMS: &'static [ParamSpec] = &[ParamSpec::float("force", 20.0), ParamSpec::string("dir", "up")];
+
+  fn build(
+    commands: &mut Commands,
+    target: Entity,
+    _: LevelId,
+    position: Vec2,
+    params: AttributeParams,
+  ) -> Result<(), AttributeError> {
+    let force = params.float("force")?;
+    let direction = MovingDirection::from_param(params.str("dir")?)
+      .ok_or_else(|| AttributeError::invalid("dir", "must be one of left, right, up or down"))?;
+
+    if force <= 0.0 {
+      return Err(AttributeError::invalid("force", "must be greater than zero"));
+    }
+
+    let direction: Vec2 = direction.into();
+
+    commands
+      .entity(target)
+      .insert(Bounce::new(direction * force))
+      .insert(ContactSubscription)
+      .insert_bundle(pickup_collider(position))
+      .insert(ColliderPositionSync::Discrete);
+
+    Ok(())
+  }
+}
+
+/// Consumes [PlayerContacted] tags, launching the player. A player who lands
+/// back on the pad while it is cooling down is launched once it can again, if
+/// they are still on it, rather than being left standing there.
+pub fn on_bounce_system(
+  mut commands: Commands,
+  time: Res<Time>,
+  mut bounce: Query<(
+    Entity,
+    &mut Bounce,
+    &ColliderPositionComponent,
+    Option<&PlayerContacted>,
+  )>,
+  mut player: Query<(
+    &mut Player,
+    &RigidBodyPositionComponent,
+    &mut RigidBodyVelocityComponent,
+  )>,
+) {
+  if let Ok((mut player_c, player_pos, mut vel)) = player.get_single_mut() {
+    let player_pos = Vec2::new(player_pos.position.translation.x, player_pos.position.translation.y);
+
+    bounce.for_each_mut(|(ent, mut bounce, position, contacted)| {
+      let pad_pos = Vec2::new(position.0.translation.x, position.0.translation.y);
+      if contacted.is_some() {
+        commands.entity(ent).remove::<PlayerContacted>();
+      }
+
+      if !bounce.step(time.delta_seconds(), contacted.is_some(), on_pad(pad_pos, player_pos)) {
+        return;
+      }
+
+      vel.linvel = bounce.velocity.into();
+
+      // Spend the current jump, so handle_player_jump neither boosts nor
+      // restarts it while the player is launched
+      player_c.jump_in_progress = true;
+      player_c.jump_boost_time = 0.0;
+      player_c.grounded = false;
+      player_c.on_moving_entity = None;
+    });
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use bevy::prelude::*;
+
+  use super::*;
+
+  #[test]
+  fn test_bounce_cooldown() {
+    let mut bounce = Bounce::new(Vec2::Y);
+    assert!(bounce.step(0.0, true, true));
+
+    // Touched during the cooldown, then left, so no launch
+    assert!(!bounce.step(BOUNCE_COOLDOWN / 2.0, true, true));
+    assert!(!bounce.step(BOUNCE_COOLDOWN / 4.0, false, false));
+    assert!(!bounce.step(BOUNCE_COOLDOWN, false, false));
+
+    // Touched during the cooldown and still on the pad once it ends
+    assert!(bounce.step(0.0, true, true));
+    assert!(!bounce.step(BOUNCE_COOLDOWN / 2.0, true, true));
+    assert!(bounce.step(BOUNCE_COOLDOWN, false, true));
+
+    assert!(on_pad(Vec2::ZERO, Vec2::new(0.9, 0.5)));
+    assert!(!on_pad(Vec2::ZERO, Vec2::new(0.0, 1.1)));
+  }
+}
@@ -0,0 +1,95 @@
+//! Keeps the camera within a rectangle while the player is inside it.
+//! `camera_zone(w, h, optional lock)`
+//!
+//! # Usage
+//! `w`, `h`: Size of the rectangle in tiles, with the sprite as its lower left
+//! tile
+//!
+//! `lock`: If true, the camera is held at the center of the rectangle rather
+//! than following the player within it (default false)
+//!
+//! The camera blends between zones as the player moves through them (see
+//! [crate::game::camera]).
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use super::{Attribute, AttributeError, AttributeParams, ParamKind, ParamSpec};
+use crate::game::camera::CameraBounds;
+use crate::game::collision_groups::*;
+use crate::level::config::SPRITE_SIZE;
+use crate::level::LevelId;
+
+#[derive(Component)]
+pub struct CameraZone {
+  /// Rectangle the camera's view is kept within, in pixels.
+  pub bounds: CameraBounds,
+  pub lock: bool,
+}
+
+impl CameraZone {
+  /// Bounds the camera is kept within while the player is in the zone.
+  pub fn framing(&self) -> CameraBounds {
+    if self.lock {
+      let center = (self.bounds.min + self.bounds.max) / 2.0;
+      CameraBounds::new(center, center)
+    } else {
+      self.bounds
+    }
+  }
+}
+
+impl Attribute for CameraZone {
+  const KEY: &'static str = "camera_zone";
+  const PARAMS: &'static [ParamSpec] = &[
+    ParamSpec::required("w", ParamKind::Number),
+    ParamSpec::required("h", ParamKind::Number),
+    ParamSpec::boolean("lock", false),
+  ];
+
+  fn build(
+    commands: &mut Commands,
+    target: Entity,
+    _: LevelId,
+    position: Vec2,
+    params: AttributeParams,
+  ) -> Result<(), AttributeError> {
+    let width = params.unsigned("w")?;
+    let height = params.unsigned("h")?;
+    let lock = params.bool("lock")?;
+
+    if width == 0 {
+      return Err(AttributeError::invalid("w", "must be at least 1"));
+    }
+    if height == 0 {
+      return Err(AttributeError::invalid("h", "must be at least 1"));
+    }
+
+    let size = Vec2::new(width as f32, height as f32);
+    let min = position - Vec2::splat(0.5);
+    let half_extents = size / 2.0;
+
+    let collider = ColliderBundle {
+      position: (min + half_extents).into(),
+      shape: ColliderShape::cuboid(half_extents.x, half_extents.y).into(),
+      material: ColliderMaterialComponent::default(),
+      flags: ColliderFlags {
+        collision_groups: ZONE_GROUP,
+        solver_groups: NONE_GROUP,
+        ..Default::default()
+      }
+      .into(),
+      ..Default::default()
+    };
+
+    commands
+      .entity(target)
+      .insert(CameraZone {
+        bounds: CameraBounds::new(min * SPRITE_SIZE as f32, (min + size) * SPRITE_SIZE as f32),
+        lock,
+      })
+      .insert_bundle(collider);
+
+    Ok(())
+  }
+}
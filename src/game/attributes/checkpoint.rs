@@ -5,11 +5,12 @@ use bevy::prelude::*;
 use bevy_kira_audio::Audio;
 use bevy_rapier2d::prelude::*;
 
-use super::lex::ParseArgumentItem;
-use super::{Attribute, Player};
+use super::{Attribute, AttributeError, AttributeParams, KeyCommands, ParamKind, ParamSpec, Player};
 use crate::game::collision::{ContactQuery, ContactSubscription, PlayerContacted};
 use crate::game::collision_groups::*;
+use crate::game::music::MusicCommands;
 use crate::game::sfx::{AudioChannels, SfxHandles};
+use crate::game::signal::SignalCommands;
 use crate::level::config::SPRITE_SIZE;
 use crate::level::load::{LevelLoadComplete, LoadLevel};
 use crate::level::LevelId;
@@ -20,40 +21,21 @@ pub struct Checkpoint(pub u32, pub Vec2);
 
 impl Attribute for Checkpoint {
   const KEY: &'static str = "checkpoint";
+  const PARAMS: &'static [ParamSpec] = &[
+    ParamSpec::required("id", ParamKind::Number),
+    ParamSpec::number("x", 0),
+    ParamSpec::number("y", 0),
+  ];
 
-  fn build(commands: &mut Commands, target: Entity, _: LevelId, position: Vec2, params: Vec<ParseArgumentItem>) {
-    let id_entry = params.get(0);
-
-    if id_entry.is_none() {
-      panic!("Attempted to construct a checkpoint without an id!");
-    }
-
-    let checkpoint_id = if let Some(ParseArgumentItem::Number(i)) = id_entry {
-      u32::try_from(*i).expect("Failed to parse number to id. Id's must be positive")
-    } else {
-      panic!("Checkpoint id is not a number!");
-    };
-
-    let player_offset = if params.len() > 0 {
-      let x_offset = params.get(1);
-      let y_offset = params.get(2);
-
-      if x_offset.is_none() || y_offset.is_none() {
-        panic!("Attempted to construct a checkpoint with an offset, but provided too few arguments!");
-      } else {
-        if let Some(ParseArgumentItem::Number(x)) = x_offset {
-          if let Some(ParseArgumentItem::Number(y)) = y_offset {
-            Vec2::new(*x as f32, *y as f32)
-          } else {
-            panic!("y coord not found!");
-          }
-        } else {
-          panic!("x coord not found!");
-        }
-      }
-    } else {
-      Vec2::ZERO
-    };
+  fn build(
+    commands: &mut Commands,
+    target: Entity,
+    _: LevelId,
+    position: Vec2,
+    params: AttributeParams,
+  ) -> Result<(), AttributeError> {
+    let checkpoint_id = params.unsigned("id")?;
+    let player_offset = Vec2::new(params.number("x")? as f32, params.number("y")? as f32);
 
     let collider = ColliderBundle {
       position: position.into(),
@@ -78,11 +60,14 @@ impl Attribute for Checkpoint {
       .insert(ContactSubscription)
       .insert_bundle(collider)
       .insert(ColliderPositionSync::Discrete);
+
+    Ok(())
   }
 }
 
 /// Consumes [PlayerContacted] tags and sets the new player respawn
-/// point.
+/// point. Signal channels, the player's gravity and the music playing are saved
+/// to be restored on respawn, and held keys are kept.
 pub fn on_checkpoint_system(
   mut commands: Commands,
   checkpoint_reached: ContactQuery<Checkpoint>,
@@ -93,6 +78,9 @@ pub fn on_checkpoint_system(
   loaded_level: Query<&LoadLevel, With<LevelLoadComplete>>,
   temple_state: Res<TempleState>,
   mut active_save: ResMut<ActiveSave>,
+  mut signal_commands: ResMut<SignalCommands>,
+  mut key_commands: ResMut<KeyCommands>,
+  mut music_commands: ResMut<MusicCommands>,
 ) {
   if let Ok(mut player) = player.get_single_mut() {
     checkpoint_reached.for_each(|(ent, checkpoint)| {
@@ -102,6 +90,10 @@ pub fn on_checkpoint_system(
           if let GameMode::InLevel(level_entry) = temple_state.game_mode {
             player.respawn_level = level.0;
             player.respawn_pos = checkpoint.1;
+            player.respawn_flipped = player.flipped;
+            signal_commands.save_checkpoint();
+            key_commands.commit();
+            music_commands.save_checkpoint();
             if let Some(save) = &mut active_save.0 {
               let key = GameSaveState::key(level_entry);
               if let Some(save) = save.level_clears.get_mut(&key) {
@@ -0,0 +1,104 @@
+//! Collectible recorded in the active save once the player touches it.
+//! `collect(id)`
+//!
+//! # Usage
+//! `id`: Number identifying the collectible within its level. Collectibles
+//! that have already been found are shown faded.
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use super::give::pickup_collider;
+use super::lex::AttributeEntry;
+use super::{Attribute, AttributeError, AttributeParams, ParamKind, ParamSpec};
+use crate::game::collision::{ContactQuery, ContactSubscription};
+use crate::level::LevelId;
+use crate::state::game_state::{write_save, ActiveSave, GameSaveState};
+
+/// Opacity of collectibles already recorded in the active save.
+const COLLECTED_ALPHA: f32 = 0.35;
+
+#[derive(Component)]
+pub struct Collect {
+  pub id: u32,
+  pub level: LevelId,
+}
+
+impl Collect {
+  /// Returns the collectible id of a `collect` attribute string, or [None] if
+  /// the string is not a valid `collect` attribute.
+  pub fn id_of(attribute: &str) -> Option<u32> {
+    let entry = AttributeEntry::parse(attribute).ok()?;
+    if entry.0 != Self::KEY {
+      return None;
+    }
+    AttributeParams::resolve(Self::PARAMS, entry.1)
+      .and_then(|params| params.unsigned("id"))
+      .ok()
+  }
+}
+
+impl Attribute for Collect {
+  const KEY: &'static str = "collect";
+  const PARAMS: &'static [ParamSpec] = &[ParamSpec::required("id", ParamKind::Number)];
+
+  fn build(
+    commands: &mut Commands,
+    target: Entity,
+    level: LevelId,
+    position: Vec2,
+    params: AttributeParams,
+  ) -> Result<(), AttributeError> {
+    let id = params.unsigned("id")?;
+
+    commands
+      .entity(target)
+      .insert(Collect { id, level })
+      .insert(ContactSubscription)
+      .insert_bundle(pickup_collider(position))
+      .insert(ColliderPositionSync::Discrete);
+
+    Ok(())
+  }
+}
+
+/// Fades collectibles which the active save has already found.
+pub fn collect_save_system(active_save: Res<ActiveSave>, mut collect: Query<(&Collect, &mut Sprite), Added<Collect>>) {
+  collect.for_each_mut(|(collect, mut sprite)| {
+    if let Some(level_state) = active_save.get_level_state(collect.level) {
+      if level_state.collected(collect.id) {
+        sprite.color.set_a(COLLECTED_ALPHA);
+      }
+    }
+  });
+}
+
+/// Records contacted collectibles in the active save and removes them.
+pub fn on_collect_system(mut commands: Commands, collect: ContactQuery<Collect>, mut active_save: ResMut<ActiveSave>) {
+  collect.for_each(|(ent, collect)| {
+    if let Some(save) = &mut active_save.0 {
+      let level_state = save.level_clears.entry(GameSaveState::key(collect.level)).or_default();
+      if level_state.collect(collect.id) {
+        info!(target: "on_collect_system", "Found collectible {} in level {}", collect.id, collect.level);
+        write_save(save);
+      }
+    } else {
+      warn!(target: "on_collect_system", "No active save to record collectible on. Ignoring...");
+    }
+
+    commands.entity(ent).despawn();
+  });
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_collect_id_of() {
+    assert_eq!(Collect::id_of("collect(3)"), Some(3));
+    assert_eq!(Collect::id_of("collect(id=4)"), Some(4));
+    assert_eq!(Collect::id_of("collect"), None);
+    assert_eq!(Collect::id_of("goal(3)"), None);
+  }
+}
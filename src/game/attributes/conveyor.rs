@@ -0,0 +1,36 @@
+//! Solid sprite that carries a grounded player sideways. `conveyor(speed)`
+//!
+//! # Usage
+//! `speed`: Tiles per second the player is carried, positive to the right
+//!
+//! Applied by the player controller as a force from the tile the hover ray
+//! lands on (see [crate::game::player]), so walls still stop the player.
+
+use bevy::prelude::*;
+
+use super::solid::solid_collider;
+use super::{Attribute, AttributeError, AttributeParams, ParamKind, ParamSpec};
+use crate::level::LevelId;
+
+#[derive(Component)]
+pub struct Conveyor(pub f32);
+
+impl Attribute for Conveyor {
+  const KEY: &'static str = "conveyor";
+  const PARAMS: &'static [ParamSpec] = &[ParamSpec::required("speed", ParamKind::Float)];
+
+  fn build(
+    commands: &mut Commands,
+    target: Entity,
+    _: LevelId,
+    position: Vec2,
+    params: AttributeParams,
+  ) -> Result<(), AttributeError> {
+    commands
+      .entity(target)
+      .insert(Conveyor(params.float("speed")?))
+      .insert_bundle(solid_collider(position));
+
+    Ok(())
+  }
+}
@@ -0,0 +1,126 @@
+//! Solid sprite that breaks shortly after the player stands on it, and later
+//! reappears, or as soon as the player respawns.
+//! `crumble(optional delay, optional respawn)`
+//!
+//! # Usage
+//! `delay`: Seconds between the player landing and the sprite breaking
+//! (default 0.5)
+//!
+//! `respawn`: Seconds until a broken sprite reappears (default 3)
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use super::solid::{set_solid, solid_collider};
+use super::{Attribute, AttributeError, AttributeParams, ParamSpec, Player};
+use crate::game::physics::PhysicsCommands;
+use crate::level::LevelId;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CrumbleState {
+  Solid,
+  /// Seconds left until the sprite breaks.
+  Crumbling(f32),
+  /// Seconds left until the sprite reappears.
+  Broken(f32),
+  /// Reappearing at once, such as after the player respawns.
+  Reset,
+}
+
+#[derive(Component)]
+pub struct Crumble {
+  pub delay: f32,
+  pub respawn: f32,
+  pub state: CrumbleState,
+}
+
+impl Crumble {
+  /// Restores the sprite, whole and solid.
+  pub fn reset(&mut self) {
+    self.state = CrumbleState::Reset;
+  }
+}
+
+impl Attribute for Crumble {
+  const KEY: &'static str = "crumble";
+  const PARAMS: &'static [ParamSpec] = &[ParamSpec::float("delay", 0.5), ParamSpec::float("respawn", 3.0)];
+
+  fn build(
+    commands: &mut Commands,
+    target: Entity,
+    _: LevelId,
+    position: Vec2,
+    params: AttributeParams,
+  ) -> Result<(), AttributeError> {
+    let delay = params.float("delay")?;
+    let respawn = params.float("respawn")?;
+
+    if delay < 0.0 {
+      return Err(AttributeError::invalid("delay", "must not be negative"));
+    }
+    if respawn < 0.0 {
+      return Err(AttributeError::invalid("respawn", "must not be negative"));
+    }
+
+    commands
+      .entity(target)
+      .insert(Crumble {
+        delay,
+        respawn,
+        state: CrumbleState::Solid,
+      })
+      .insert_bundle(solid_collider(position));
+
+    Ok(())
+  }
+}
+
+/// Starts crumbling sprites the player is grounded on, fading them out, and
+/// breaks and restores them as their timers run out.
+pub fn crumble_system(
+  time: Res<Time>,
+  physics_commands: Res<PhysicsCommands>,
+  player: Query<&Player>,
+  mut crumble: Query<(Entity, &mut Crumble, &mut ColliderFlagsComponent, &mut Sprite)>,
+) {
+  if physics_commands.paused() {
+    return;
+  }
+
+  let ground_entity = player.get_single().ok().and_then(|player| player.ground_entity);
+  let delta_t = time.delta_seconds();
+
+  crumble.for_each_mut(|(ent, mut crumble, mut flags, mut sprite)| match crumble.state {
+    CrumbleState::Solid => {
+      if ground_entity == Some(ent) {
+        crumble.state = CrumbleState::Crumbling(crumble.delay);
+      }
+    },
+    CrumbleState::Crumbling(remaining) => {
+      let remaining = remaining - delta_t;
+      if remaining <= 0.0 {
+        crumble.state = CrumbleState::Broken(crumble.respawn);
+        set_solid(&mut flags, false);
+        sprite.color.set_a(0.0);
+      } else {
+        crumble.state = CrumbleState::Crumbling(remaining);
+        sprite.color.set_a(0.25 + 0.75 * remaining / crumble.delay);
+      }
+    },
+    CrumbleState::Broken(remaining) => {
+      let remaining = remaining - delta_t;
+      if remaining <= 0.0 {
+        crumble.state = CrumbleState::Solid;
+        set_solid(&mut flags, true);
+        sprite.color.set_a(1.0);
+      } else {
+        crumble.state = CrumbleState::Broken(remaining);
+      }
+    },
+    CrumbleState::Reset => {
+      crumble.state = CrumbleState::Solid;
+      set_solid(&mut flags, true);
+      sprite.color.set_a(1.0);
+    },
+  });
+}
@@ -1,11 +1,11 @@
-//! Grants the player a dash. `dash()`
+//! Grants the player a dash. `dash(optional capacity)`
 
 use std::mem;
 
 use bevy::prelude::*;
 
-use super::lex::ParseArgumentItem;
-use super::Attribute;
+use super::{Attribute, AttributeError, AttributeParams, ParamSpec};
+use crate::game::orbs::PlayerOrbCommands;
 use crate::level::config::SPRITE_SIZE;
 use crate::level::LevelId;
 
@@ -15,21 +15,13 @@ pub struct Dash {
   capacity: u32,
   holding: bool,
   hold_vector: Vec2,
-}
-
-impl Default for Dash {
-  fn default() -> Self {
-    const STARTING_CAP: u32 = 1;
-    Self {
-      charges: 0,
-      capacity: STARTING_CAP,
-      holding: Default::default(),
-      hold_vector: Default::default(),
-    }
-  }
+  /// Seconds left of the last dash, while it can defeat enemies.
+  dash_time: f32,
 }
 
 const MAX_DIST_SQUARED: f32 = 3.0 * SPRITE_SIZE as f32;
+/// Seconds after releasing a dash that the player counts as dashing.
+const DASH_TIME: f32 = 0.3;
 
 #[derive(Component)]
 pub struct DashCrosshair;
@@ -38,6 +30,17 @@ pub struct DashCrosshair;
 pub struct DashCounter(pub u32);
 
 impl Dash {
+  /// Dash that restores up to `capacity` charges.
+  pub fn new(capacity: u32) -> Self {
+    Self {
+      charges: 0,
+      capacity,
+      holding: Default::default(),
+      hold_vector: Default::default(),
+      dash_time: 0.0,
+    }
+  }
+
   pub fn can_dash(&self) -> bool {
     self.charges > 0
   }
@@ -63,6 +66,7 @@ impl Dash {
   pub fn release(&mut self) -> Vec2 {
     self.charges = (self.charges as i32 - 1).max(0) as u32;
     self.holding = false;
+    self.dash_time = DASH_TIME;
     let mut result = Vec2::default();
     mem::swap(&mut self.hold_vector, &mut result);
     result
@@ -74,20 +78,57 @@ impl Dash {
     charge_changing
   }
 
-  #[allow(dead_code)]
-  pub fn set_cap(&mut self, cap: u32) {
-    self.capacity = cap;
-  }
-
   pub fn charges(&self) -> u32 {
     self.charges
   }
+
+  /// Returns true shortly after a dash is released.
+  pub fn dashing(&self) -> bool {
+    self.dash_time > 0.0
+  }
+
+  pub fn tick(&mut self, delta: f32) {
+    self.dash_time = (self.dash_time - delta).max(0.0);
+  }
 }
 
 impl Attribute for Dash {
   const KEY: &'static str = "dash";
+  const PARAMS: &'static [ParamSpec] = &[ParamSpec::number("capacity", 1)];
+  const ABILITY: bool = true;
+
+  fn build(
+    commands: &mut Commands,
+    target: Entity,
+    _: LevelId,
+    _: Vec2,
+    params: AttributeParams,
+  ) -> Result<(), AttributeError> {
+    let capacity = params.unsigned("capacity")?;
+    if capacity == 0 {
+      return Err(AttributeError::invalid("capacity", "must be at least 1"));
+    }
+
+    commands.entity(target).insert(Dash::new(capacity));
+
+    Ok(())
+  }
+
+  fn remove(commands: &mut Commands, target: Entity) {
+    commands.entity(target).remove::<Dash>();
+  }
+}
 
-  fn build(commands: &mut Commands, target: Entity, _: LevelId, _: Vec2, _: Vec<ParseArgumentItem>) {
-    commands.entity(target).insert(Dash::default());
+/// Clears the crosshair and dash orbs once the player loses their [Dash], such
+/// as from `take(dash)`.
+pub fn on_dash_removed_system(
+  mut commands: Commands,
+  removed: RemovedComponents<Dash>,
+  crosshair: Query<Entity, With<DashCrosshair>>,
+  mut orb_commands: ResMut<PlayerOrbCommands>,
+) {
+  if removed.iter().next().is_some() {
+    crosshair.for_each(|ent| commands.entity(ent).despawn());
+    orb_commands.set_dash_count(0);
   }
 }
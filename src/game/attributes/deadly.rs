@@ -3,11 +3,14 @@
 use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
 
-use super::lex::ParseArgumentItem;
-use super::{Attribute, Player};
+use super::{
+  Attribute, AttributeError, AttributeParams, Crumble, Enemy, Falling, KeyCommands, MovementGroupCommands, Player,
+};
 use crate::game::collision::{ContactSubscription, ContactTagQuery, PlayerContacted};
 use crate::game::collision_groups::*;
-use crate::level::load::{LevelLoadComplete, LoadLevel, TransitionLevel};
+use crate::game::music::MusicCommands;
+use crate::game::signal::SignalCommands;
+use crate::level::load::{LevelLoadComplete, LoadLevel, Respawn, TransitionLevel};
 use crate::level::LevelId;
 
 #[derive(Component)]
@@ -16,7 +19,13 @@ pub struct Deadly;
 impl Attribute for Deadly {
   const KEY: &'static str = "deadly";
 
-  fn build(commands: &mut Commands, target: Entity, _: LevelId, position: Vec2, _: Vec<ParseArgumentItem>) {
+  fn build(
+    commands: &mut Commands,
+    target: Entity,
+    _: LevelId,
+    position: Vec2,
+    _: AttributeParams,
+  ) -> Result<(), AttributeError> {
     let collider = ColliderBundle {
       position: position.into(),
       shape: ColliderShape::cuboid(0.5, 0.5).into(),
@@ -37,23 +46,54 @@ impl Attribute for Deadly {
       .insert(ContactSubscription)
       .insert_bundle(collider)
       .insert(ColliderPositionSync::Discrete);
+
+    Ok(())
   }
 }
 
-/// Consumes [PlayerContacted] tags and respawns the player.
+/// Consumes [PlayerContacted] tags and respawns the player. Moving sprites are
+/// reset so each attempt starts the same, defeated enemies and broken or fallen
+/// platforms return, and signal channels, keys, music and the player's gravity
+/// are restored to when the checkpoint was reached. Enemies the player has
+/// just defeated are harmless.
 pub fn on_death_system(
   mut commands: Commands,
   deadly_contacted: ContactTagQuery<Deadly>,
   loaded_level: Query<&LoadLevel, With<LevelLoadComplete>>,
-  mut player: Query<(&mut RigidBodyPositionComponent, &Player)>,
+  mut player: Query<(&mut RigidBodyPositionComponent, &mut Player)>,
+  mut group_commands: ResMut<MovementGroupCommands>,
+  mut signal_commands: ResMut<SignalCommands>,
+  mut key_commands: ResMut<KeyCommands>,
+  mut music_commands: ResMut<MusicCommands>,
+  mut enemies: Query<&mut Enemy>,
+  mut crumbles: Query<&mut Crumble>,
+  mut fallings: Query<&mut Falling>,
 ) {
-  if let Ok((mut pos, player)) = player.get_single_mut() {
+  if let Ok((mut pos, mut player)) = player.get_single_mut() {
     deadly_contacted.for_each(|ent| {
+      if enemies.get(ent).map_or(false, |enemy| enemy.defeated()) {
+        commands.entity(ent).remove::<PlayerContacted>();
+        return;
+      }
+
       let level_id = loaded_level.get_single().unwrap().0;
       if player.respawn_level != level_id {
-        commands.spawn().insert(TransitionLevel(player.respawn_level));
+        commands
+          .spawn()
+          .insert(TransitionLevel(player.respawn_level))
+          .insert(Respawn);
       } else {
         pos.position.translation = player.respawn_pos.into();
+        if player.flipped != player.respawn_flipped {
+          player.flip();
+        }
+        group_commands.reset();
+        signal_commands.restore_checkpoint();
+        key_commands.revert();
+        music_commands.restore_checkpoint();
+        enemies.for_each_mut(|mut enemy| enemy.reset());
+        crumbles.for_each_mut(|mut crumble| crumble.reset());
+        fallings.for_each_mut(|mut falling| falling.reset());
       }
 
       commands.entity(ent).remove::<PlayerContacted>();
@@ -0,0 +1,112 @@
+//! Dialogue read by pressing `UP` while overlapping the sprite. `dialogue(id)`
+//!
+//! # Usage
+//! `id`: Name of the script in the level manifest's `dialogue` table, which
+//! holds a list of pages:
+//!
+//! ```toml
+//! [dialogue]
+//! hermit = ['Oh, a visitor.', 'Mind the spikes.']
+//! ```
+//!
+//! Player input is revoked while the dialogue is open, even if the game is
+//! paused and unpaused. Each press of `UP` moves to the next page, closing the
+//! dialogue after the last.
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use kurinji::Kurinji;
+
+use super::zone::{player_zones, zone_collider};
+use super::{Attribute, AttributeError, AttributeParams, ParamKind, ParamSpec, Player, Sign};
+use crate::game::dialogue::DialogueBox;
+use crate::game::physics::PhysicsCommands;
+use crate::game::player::{InputHold, PlayerInputCommands};
+use crate::input::UP;
+use crate::level::config::SPRITE_SIZE;
+use crate::level::load::{LevelLoadComplete, PreparedLevel};
+use crate::level::LevelId;
+
+#[derive(Component)]
+pub struct Dialogue {
+  pub id: String,
+}
+
+impl Attribute for Dialogue {
+  const KEY: &'static str = "dialogue";
+  const PARAMS: &'static [ParamSpec] = &[ParamSpec::required("id", ParamKind::Str)];
+
+  fn build(
+    commands: &mut Commands,
+    target: Entity,
+    _: LevelId,
+    position: Vec2,
+    params: AttributeParams,
+  ) -> Result<(), AttributeError> {
+    commands
+      .entity(target)
+      .insert(Dialogue {
+        id: params.str("id")?.to_string(),
+      })
+      .insert_bundle(zone_collider(position));
+
+    Ok(())
+  }
+}
+
+/// Shows the text of the sign the player is overlapping, and opens and
+/// advances dialogues with `UP`. Input is returned to the player once a
+/// dialogue closes.
+pub fn dialogue_system(
+  input: Res<Kurinji>,
+  mut up_held: Local<bool>,
+  physics_commands: Res<PhysicsCommands>,
+  mut player_input_commands: ResMut<PlayerInputCommands>,
+  mut dialogue_box: ResMut<DialogueBox>,
+  query_pipeline: Res<QueryPipeline>,
+  collider_query: QueryPipelineColliderComponentsQuery,
+  level: Query<&PreparedLevel, With<LevelLoadComplete>>,
+  player: Query<&Transform, With<Player>>,
+  signs: Query<&Sign>,
+  dialogues: Query<&Dialogue>,
+) {
+  if physics_commands.paused() {
+    return;
+  }
+
+  let pressed = input.is_action_active(UP) && !*up_held;
+  *up_held = input.is_action_active(UP);
+
+  if dialogue_box.is_open() {
+    if pressed && !dialogue_box.advance() {
+      player_input_commands.grant_input(InputHold::Dialogue);
+    }
+    return;
+  }
+
+  if let Ok(trans) = player.get_single() {
+    let point = trans.translation.truncate() / SPRITE_SIZE as f32;
+    let zones = player_zones(&query_pipeline, &collider_query, point);
+
+    let sign = zones.iter().find_map(|&zone| signs.get(zone).ok());
+    dialogue_box.set_sign(sign.map(|sign| sign.0.clone()));
+
+    if !pressed || !player_input_commands.player_has_input() {
+      return;
+    }
+
+    if let Some(dialogue) = zones.iter().find_map(|&zone| dialogues.get(zone).ok()) {
+      let pages = level
+        .get_single()
+        .ok()
+        .and_then(|level| level.0.dialogue.get(&dialogue.id).cloned())
+        .unwrap_or_default();
+
+      if dialogue_box.open(pages) {
+        player_input_commands.revoke_input(InputHold::Dialogue);
+      } else {
+        warn!(target: "dialogue_system", "No pages for dialogue `{}`. Ignoring...", dialogue.id);
+      }
+    }
+  }
+}
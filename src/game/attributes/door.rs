@@ -0,0 +1,92 @@
+//! Solid sprite that opens while a signal channel is active. `door(channel,
+//! optional open texture, optional invert)`
+//!
+//! # Usage
+//! `channel`: Signal channel that opens the door
+//!
+//! `texture`: Texture shown while open. If not given, the door fades instead.
+//!
+//! `invert`: Open while the channel is inactive instead (default false)
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use super::solid::{set_solid, solid_collider};
+use super::{Attribute, AttributeError, AttributeParams, ParamKind, ParamSpec};
+use crate::game::signal::{SignalChannel, Signals};
+use crate::level::util::get_texture_path;
+use crate::level::LevelId;
+
+#[derive(Component)]
+pub struct Door {
+  pub channel: SignalChannel,
+  pub open_texture: Option<String>,
+  pub invert: bool,
+  open: bool,
+  closed_texture: Option<Handle<Image>>,
+}
+
+impl Attribute for Door {
+  const KEY: &'static str = "door";
+  const PARAMS: &'static [ParamSpec] = &[
+    ParamSpec::required("channel", ParamKind::Number),
+    ParamSpec::optional("texture", ParamKind::Str),
+    ParamSpec::boolean("invert", false),
+  ];
+
+  fn build(
+    commands: &mut Commands,
+    target: Entity,
+    _: LevelId,
+    position: Vec2,
+    params: AttributeParams,
+  ) -> Result<(), AttributeError> {
+    let open_texture = if params.is_set("texture") {
+      Some(params.str("texture")?.to_string())
+    } else {
+      None
+    };
+
+    commands
+      .entity(target)
+      .insert(Door {
+        channel: params.unsigned("channel")?,
+        open_texture,
+        invert: params.bool("invert")?,
+        open: false,
+        closed_texture: None,
+      })
+      .insert_bundle(solid_collider(position));
+
+    Ok(())
+  }
+}
+
+/// Opens and closes doors to match their channel, toggling their collider and
+/// texture.
+pub fn door_system(
+  signals: Res<Signals>,
+  asset_server: Res<AssetServer>,
+  mut doors: Query<(&mut Door, &mut ColliderFlagsComponent, &mut Handle<Image>, &mut Sprite)>,
+) {
+  doors.for_each_mut(|(mut door, mut flags, mut texture, mut sprite)| {
+    let open = signals.active(door.channel) != door.invert;
+    if open == door.open {
+      return;
+    }
+
+    door.open = open;
+    set_solid(&mut flags, !open);
+
+    if let Some(open_texture) = door.open_texture.clone() {
+      if open {
+        door.closed_texture = Some(texture.clone());
+        *texture = asset_server.load(get_texture_path(&open_texture));
+      } else if let Some(closed_texture) = door.closed_texture.take() {
+        *texture = closed_texture;
+      }
+    } else {
+      sprite.color.set_a(if open { 0.25 } else { 1.0 });
+    }
+  });
+}
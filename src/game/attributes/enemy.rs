@@ -0,0 +1,144 @@
+//! Enemy that kills the player on contact, unless the player lands on it or
+//! dashes into it. `enemy(kind, optional speed)`
+//!
+//! # Usage
+//! `kind`: How the enemy moves (see [crate::game::ai])
+//! - `walker`: Patrols along the ground, turning at walls and ledges
+//! - `flyer`: Homes towards the player when close
+//!
+//! `speed`: Tiles per second (default 3)
+//!
+//! Defeated enemies return when the player respawns.
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use super::{Attribute, AttributeError, AttributeParams, Dash, Deadly, ParamKind, ParamSpec, Player};
+use crate::game::ai::{EnemyKind, EnemyState};
+use crate::game::collision::{ContactSubscription, PlayerContacted};
+use crate::game::collision_groups::*;
+use crate::level::LevelId;
+
+/// Speed the player bounces off an enemy they land on, away from their
+/// gravity.
+const STOMP_BOUNCE: f32 = 8.0;
+
+#[derive(Component)]
+pub struct Enemy {
+  pub kind: EnemyKind,
+  pub speed: f32,
+  pub state: EnemyState,
+  /// Facing direction, -1 for left and 1 for right.
+  pub direction: f32,
+  pub home: Vec2,
+}
+
+impl Enemy {
+  pub fn defeated(&self) -> bool {
+    self.state == EnemyState::Defeated
+  }
+
+  /// Returns the enemy to where it started, undefeated.
+  pub fn reset(&mut self) {
+    self.state = EnemyState::Reset;
+  }
+}
+
+impl Attribute for Enemy {
+  const KEY: &'static str = "enemy";
+  const PARAMS: &'static [ParamSpec] = &[
+    ParamSpec::required("kind", ParamKind::Str),
+    ParamSpec::float("speed", 3.0),
+  ];
+
+  fn build(
+    commands: &mut Commands,
+    target: Entity,
+    _: LevelId,
+    position: Vec2,
+    params: AttributeParams,
+  ) -> Result<(), AttributeError> {
+    let kind = EnemyKind::from_param(params.str("kind")?)
+      .ok_or_else(|| AttributeError::invalid("kind", "must be one of walker or flyer"))?;
+    let speed = params.float("speed")?;
+
+    if speed < 0.0 {
+      return Err(AttributeError::invalid("speed", "must not be negative"));
+    }
+
+    let collider = ColliderBundle {
+      position: position.into(),
+      shape: ColliderShape::cuboid(0.4, 0.4).into(),
+      material: ColliderMaterialComponent::default(),
+      flags: ColliderFlags {
+        collision_groups: DEADLY_GROUP,
+        solver_groups: NONE_GROUP,
+        active_events: ActiveEvents::CONTACT_EVENTS,
+        ..Default::default()
+      }
+      .into(),
+      ..Default::default()
+    };
+
+    commands
+      .entity(target)
+      .insert(Enemy {
+        kind,
+        speed,
+        state: EnemyState::Patrol,
+        direction: 1.0,
+        home: position,
+      })
+      .insert(Deadly)
+      .insert(ContactSubscription)
+      .insert_bundle(collider)
+      .insert(ColliderPositionSync::Discrete);
+
+    Ok(())
+  }
+}
+
+/// Defeats contacted enemies the player lands on or dashes into. Enemies that
+/// aren't defeated are left for [super::on_death_system].
+pub fn on_enemy_system(
+  mut enemies: Query<
+    (
+      &mut Enemy,
+      &ColliderPositionComponent,
+      &mut ColliderFlagsComponent,
+      &mut Visibility,
+    ),
+    With<PlayerContacted>,
+  >,
+  mut player: Query<(
+    &mut Player,
+    &RigidBodyPositionComponent,
+    &mut RigidBodyVelocityComponent,
+    Option<&Dash>,
+  )>,
+) {
+  if let Ok((mut player_c, player_pos, mut vel, dash)) = player.get_single_mut() {
+    enemies.for_each_mut(|(mut enemy, position, mut flags, mut visibility)| {
+      if enemy.defeated() {
+        return;
+      }
+
+      let up = player_c.up();
+      let stomped =
+        (player_pos.position.translation.y - position.0.translation.y) * up > 0.5 && vel.linvel.y * up <= 0.0;
+      let dashed = dash.map_or(false, |dash| dash.dashing());
+
+      if stomped || dashed {
+        enemy.state = EnemyState::Defeated;
+        flags.collision_groups = NONE_GROUP;
+        visibility.is_visible = false;
+
+        if stomped {
+          vel.linvel.y = STOMP_BOUNCE * up;
+          player_c.jump_in_progress = true;
+          player_c.grounded = false;
+        }
+      }
+    });
+  }
+}
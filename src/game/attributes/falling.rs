@@ -0,0 +1,169 @@
+//! Solid sprite that drops once the player stands on it, and later returns to
+//! where it started, or as soon as the player respawns.
+//! `falling(optional delay, optional respawn)`
+//!
+//! # Usage
+//! `delay`: Seconds between the player landing and the sprite dropping
+//! (default 0.3)
+//!
+//! `respawn`: Seconds after the sprite has fallen out of the level until it
+//! returns (default 3)
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use super::solid::{set_solid, solid_collider};
+use super::{Attribute, AttributeError, AttributeParams, ParamSpec, PlatformDelta, Player};
+use crate::game::physics::PhysicsCommands;
+use crate::level::LevelId;
+
+/// Acceleration of a falling sprite, in tiles per second squared.
+const FALL_ACCELERATION: f32 = 30.0;
+/// Distance in tiles a sprite falls before it is removed until respawn.
+const MAX_FALL_DISTANCE: f32 = 40.0;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FallingState {
+  Resting,
+  /// Seconds left until the sprite drops.
+  Shaking(f32),
+  /// Current downwards speed, in tiles per second.
+  Falling(f32),
+  /// Seconds left until the sprite returns.
+  Gone(f32),
+  /// Returning at once, such as after the player respawns.
+  Reset,
+}
+
+#[derive(Component)]
+pub struct Falling {
+  pub delay: f32,
+  pub respawn: f32,
+  pub state: FallingState,
+  starting_position: Vec2,
+  distance: f32,
+}
+
+impl Falling {
+  /// Returns the sprite to where it started, solid.
+  pub fn reset(&mut self) {
+    self.state = FallingState::Reset;
+  }
+}
+
+impl Attribute for Falling {
+  const KEY: &'static str = "falling";
+  const PARAMS: &'static [ParamSpec] = &[ParamSpec::float("delay", 0.3), ParamSpec::float("respawn", 3.0)];
+
+  fn build(
+    commands: &mut Commands,
+    target: Entity,
+    _: LevelId,
+    position: Vec2,
+    params: AttributeParams,
+  ) -> Result<(), AttributeError> {
+    let delay = params.float("delay")?;
+    let respawn = params.float("respawn")?;
+
+    if delay < 0.0 {
+      return Err(AttributeError::invalid("delay", "must not be negative"));
+    }
+    if respawn < 0.0 {
+      return Err(AttributeError::invalid("respawn", "must not be negative"));
+    }
+
+    commands
+      .entity(target)
+      .insert(Falling {
+        delay,
+        respawn,
+        state: FallingState::Resting,
+        starting_position: position,
+        distance: 0.0,
+      })
+      .insert(PlatformDelta::default())
+      .insert_bundle(solid_collider(position))
+      .insert(ColliderPositionSync::Discrete);
+
+    Ok(())
+  }
+}
+
+/// Drops falling sprites once the player is grounded on them, carrying the
+/// player with them (see [super::move_player]). Sprites that have fallen out
+/// of the level are hidden like broken [super::Crumble] sprites.
+pub fn falling_system(
+  time: Res<Time>,
+  physics_commands: Res<PhysicsCommands>,
+  player: Query<&Player>,
+  mut falling: Query<(
+    Entity,
+    &mut Falling,
+    &mut PlatformDelta,
+    &mut ColliderPositionComponent,
+    &mut ColliderFlagsComponent,
+    &mut Sprite,
+  )>,
+) {
+  if physics_commands.paused() {
+    return;
+  }
+
+  let ground_entity = player.get_single().ok().and_then(|player| player.ground_entity);
+  let delta_t = time.delta_seconds();
+
+  falling.for_each_mut(
+    |(ent, mut falling, mut platform_delta, mut collider_position, mut flags, mut sprite)| {
+      platform_delta.0 = Vec2::ZERO;
+
+      match falling.state {
+        FallingState::Resting => {
+          if ground_entity == Some(ent) {
+            falling.state = FallingState::Shaking(falling.delay);
+          }
+        },
+        FallingState::Shaking(remaining) => {
+          let remaining = remaining - delta_t;
+          falling.state = if remaining <= 0.0 {
+            FallingState::Falling(0.0)
+          } else {
+            FallingState::Shaking(remaining)
+          };
+        },
+        FallingState::Falling(speed) => {
+          let speed = speed + FALL_ACCELERATION * delta_t;
+          let step = speed * delta_t;
+          falling.distance += step;
+
+          if falling.distance >= MAX_FALL_DISTANCE {
+            falling.state = FallingState::Gone(falling.respawn);
+            set_solid(&mut flags, false);
+            sprite.color.set_a(0.0);
+          } else {
+            falling.state = FallingState::Falling(speed);
+            platform_delta.0 = Vec2::new(0.0, -step);
+          }
+        },
+        FallingState::Gone(remaining) => {
+          let remaining = remaining - delta_t;
+          if remaining <= 0.0 {
+            falling.state = FallingState::Resting;
+            falling.distance = 0.0;
+            set_solid(&mut flags, true);
+            sprite.color.set_a(1.0);
+          } else {
+            falling.state = FallingState::Gone(remaining);
+          }
+        },
+        FallingState::Reset => {
+          falling.state = FallingState::Resting;
+          falling.distance = 0.0;
+          set_solid(&mut flags, true);
+          sprite.color.set_a(1.0);
+        },
+      }
+
+      collider_position.0 = (falling.starting_position - Vec2::new(0.0, falling.distance)).into();
+    },
+  );
+}
@@ -0,0 +1,34 @@
+//! Grants the player the ability to flip their own gravity by pressing `FLIP`
+//! while grounded. `flip()`
+
+use bevy::prelude::*;
+
+use super::{Attribute, AttributeError, AttributeParams};
+use crate::level::LevelId;
+
+#[derive(Component, Default)]
+pub struct Flip {
+  /// Set while `FLIP` is held, so each press flips once.
+  pub pressed: bool,
+}
+
+impl Attribute for Flip {
+  const KEY: &'static str = "flip";
+  const ABILITY: bool = true;
+
+  fn build(
+    commands: &mut Commands,
+    target: Entity,
+    _: LevelId,
+    _: Vec2,
+    _: AttributeParams,
+  ) -> Result<(), AttributeError> {
+    commands.entity(target).insert(Flip::default());
+
+    Ok(())
+  }
+
+  fn remove(commands: &mut Commands, target: Entity) {
+    commands.entity(target).remove::<Flip>();
+  }
+}
@@ -0,0 +1,69 @@
+//! Inverts the player's gravity on contact. `flip_gravity()`
+//!
+//! The player falls upwards until they touch another, hovering under ceilings
+//! and jumping downwards. Gravity is restored to how it was at the last
+//! checkpoint if the player dies.
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use super::give::pickup_collider;
+use super::{Attribute, AttributeError, AttributeParams, Player};
+use crate::game::collision::{ContactSubscription, PlayerContacted};
+use crate::level::LevelId;
+
+/// Seconds before a trigger can flip the player again.
+const FLIP_COOLDOWN: f32 = 0.5;
+
+#[derive(Component)]
+pub struct FlipGravity {
+  /// Seconds left until the trigger can flip the player again.
+  cooldown: f32,
+}
+
+impl Attribute for FlipGravity {
+  const KEY: &'static str = "flip_gravity";
+
+  fn build(
+    commands: &mut Commands,
+    target: Entity,
+    _: LevelId,
+    position: Vec2,
+    _: AttributeParams,
+  ) -> Result<(), AttributeError> {
+    commands
+      .entity(target)
+      .insert(FlipGravity { cooldown: 0.0 })
+      .insert(ContactSubscription)
+      .insert_bundle(pickup_collider(position))
+      .insert(ColliderPositionSync::Discrete);
+
+    Ok(())
+  }
+}
+
+/// Consumes [PlayerContacted] tags, flipping the player's gravity if the
+/// trigger is not cooling down.
+pub fn on_flip_gravity_system(
+  mut commands: Commands,
+  time: Res<Time>,
+  mut triggers: Query<(Entity, &mut FlipGravity, Option<&PlayerContacted>)>,
+  mut player: Query<&mut Player>,
+) {
+  triggers.for_each_mut(|(ent, mut trigger, contacted)| {
+    trigger.cooldown = (trigger.cooldown - time.delta_seconds()).max(0.0);
+
+    if contacted.is_none() {
+      return;
+    }
+
+    if trigger.cooldown <= 0.0 {
+      if let Ok(mut player_c) = player.get_single_mut() {
+        player_c.flip();
+        trigger.cooldown = FLIP_COOLDOWN;
+      }
+    }
+
+    commands.entity(ent).remove::<PlayerContacted>();
+  });
+}
@@ -0,0 +1,221 @@
+//! Pans the camera to a tile when the player touches the sprite, such as to
+//! show a door opening or a distant goal. `focus(x, y, seconds, optional once)`
+//!
+//! # Usage
+//! `x`, `y`: Tile in the level to show
+//!
+//! `seconds`: Time the camera is held on the tile before returning to the
+//! player
+//!
+//! `once`: If true, the focus only plays the first time it is touched, and
+//! not again after respawning, even when respawning reloads the level
+//! (default true)
+//!
+//! Player input is revoked until the camera is returned, or a new level loads.
+
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use super::give::pickup_collider;
+use super::{Attribute, AttributeError, AttributeParams, ParamKind, ParamSpec};
+use crate::game::camera::CameraTarget;
+use crate::game::collision::{ContactSubscription, PlayerContacted};
+use crate::game::physics::PhysicsCommands;
+use crate::game::player::{InputHold, PlayerInputCommands};
+use crate::level::config::SPRITE_SIZE;
+use crate::level::load::{LevelLoadComplete, LevelLoadedSprite, LoadLevel, Respawn};
+use crate::level::LevelId;
+
+#[derive(Component)]
+pub struct Focus {
+  /// Tile to show.
+  pub target: Vec2,
+  pub duration: f32,
+  pub once: bool,
+  /// Level and tile of the focus sprite, see [PlayedFoci].
+  key: FocusKey,
+}
+
+/// Level and tile of a focus sprite.
+type FocusKey = (LevelId, i32, i32);
+
+/// [Res] of the foci that have played, kept when respawning reloads a level
+/// so `once` foci don't replay. A level's foci are forgotten when it is
+/// entered afresh.
+#[derive(Default)]
+pub struct PlayedFoci(HashSet<FocusKey>);
+
+/// [CameraTarget] spawned by a [Focus], removed once its time is up.
+#[derive(Component)]
+pub struct FocusTarget {
+  /// Seconds until the camera returns to the player.
+  remaining: f32,
+}
+
+impl Attribute for Focus {
+  const KEY: &'static str = "focus";
+  const PARAMS: &'static [ParamSpec] = &[
+    ParamSpec::required("x", ParamKind::Number),
+    ParamSpec::required("y", ParamKind::Number),
+    ParamSpec::required("seconds", ParamKind::Float),
+    ParamSpec::boolean("once", true),
+  ];
+
+  fn build(
+    commands: &mut Commands,
+    target: Entity,
+    level: LevelId,
+    position: Vec2,
+    params: AttributeParams,
+  ) -> Result<(), AttributeError> {
+    let focus_target = Vec2::new(params.number("x")? as f32, params.number("y")? as f32);
+    let duration = params.float("seconds")?;
+
+    if duration <= 0.0 {
+      return Err(AttributeError::invalid("seconds", "must be greater than zero"));
+    }
+
+    commands
+      .entity(target)
+      .insert(Focus {
+        target: focus_target,
+        duration,
+        once: params.bool("once")?,
+        key: (level, position.x as i32, position.y as i32),
+      })
+      .insert(ContactSubscription)
+      .insert_bundle(pickup_collider(position))
+      .insert(ColliderPositionSync::Discrete);
+
+    Ok(())
+  }
+}
+
+/// Consumes [PlayerContacted] tags, spawning a [CameraTarget] at the focused
+/// tile and revoking input. Only one focus plays at a time. A focus cut short
+/// by its level unloading returns input once the next level loads.
+pub fn on_focus_system(
+  mut commands: Commands,
+  focus: Query<(Entity, &Focus), With<PlayerContacted>>,
+  active: Query<(), With<FocusTarget>>,
+  level_loaded: Query<(&LoadLevel, Option<&Respawn>), Added<LevelLoadComplete>>,
+  mut played: ResMut<PlayedFoci>,
+  mut player_input_commands: ResMut<PlayerInputCommands>,
+) {
+  if let Some((level, respawn)) = level_loaded.iter().next() {
+    player_input_commands.grant_input(InputHold::Focus);
+    if respawn.is_none() {
+      played.0.retain(|key| key.0 != level.0);
+    }
+  }
+
+  let mut playing = active.iter().next().is_some();
+
+  focus.for_each(|(ent, focus)| {
+    commands.entity(ent).remove::<PlayerContacted>();
+
+    if playing || (focus.once && played.0.contains(&focus.key)) {
+      return;
+    }
+
+    commands
+      .spawn()
+      .insert(Transform::from_translation(
+        (focus.target * SPRITE_SIZE as f32).extend(0.0),
+      ))
+      .insert(GlobalTransform::default())
+      .insert(CameraTarget)
+      .insert(FocusTarget {
+        remaining: focus.duration,
+      })
+      .insert(LevelLoadedSprite);
+
+    player_input_commands.revoke_input(InputHold::Focus);
+    played.0.insert(focus.key);
+    playing = true;
+  });
+}
+
+/// Removes focus camera targets once their time is up, returning the camera
+/// and input to the player. Paused along with physics.
+pub fn focus_target_system(
+  mut commands: Commands,
+  time: Res<Time>,
+  physics_commands: Res<PhysicsCommands>,
+  mut targets: Query<(Entity, &mut FocusTarget)>,
+  mut player_input_commands: ResMut<PlayerInputCommands>,
+) {
+  if physics_commands.paused() {
+    return;
+  }
+
+  targets.for_each_mut(|(ent, mut target)| {
+    target.remaining -= time.delta_seconds();
+    if target.remaining <= 0.0 {
+      commands.entity(ent).despawn();
+      player_input_commands.grant_input(InputHold::Focus);
+    }
+  });
+}
+
+#[cfg(test)]
+mod tests {
+  use bevy::prelude::*;
+
+  use super::*;
+
+  fn load(world: &mut World, respawn: bool) {
+    let mut level = world.spawn();
+    level.insert(LoadLevel(1)).insert(LevelLoadComplete);
+    if respawn {
+      level.insert(Respawn);
+    }
+    world.spawn().insert(PlayerContacted).insert(Focus {
+      target: Vec2::ZERO,
+      duration: 1.0,
+      once: true,
+      key: (1, 2, 3),
+    });
+  }
+
+  /// Despawns the level and any focus target, returning whether a focus was
+  /// playing.
+  fn unload(world: &mut World) -> bool {
+    let targets: Vec<Entity> = world
+      .query_filtered::<Entity, With<FocusTarget>>()
+      .iter(world)
+      .collect();
+    let level: Vec<Entity> = world.query_filtered::<Entity, With<LoadLevel>>().iter(world).collect();
+    let focus: Vec<Entity> = world.query_filtered::<Entity, With<Focus>>().iter(world).collect();
+    for ent in targets.iter().chain(level.iter()).chain(focus.iter()) {
+      world.despawn(*ent);
+    }
+    !targets.is_empty()
+  }
+
+  #[test]
+  fn test_focus_once() {
+    let mut world = World::default();
+    let mut update_stage = SystemStage::single_threaded();
+
+    world.insert_resource(PlayedFoci::default());
+    world.insert_resource(PlayerInputCommands::default());
+    update_stage.add_system(on_focus_system);
+
+    load(&mut world, false);
+    update_stage.run(&mut world);
+    assert!(unload(&mut world));
+
+    // Respawning into the level doesn't replay the focus
+    load(&mut world, true);
+    update_stage.run(&mut world);
+    assert!(!unload(&mut world));
+
+    // Entering the level afresh does
+    load(&mut world, false);
+    update_stage.run(&mut world);
+    assert!(unload(&mut world));
+  }
+}
@@ -0,0 +1,37 @@
+//! Pushes the player while they are inside the sprite, for wind and currents.
+//! `force(x, y)`
+//!
+//! # Usage
+//! `x`, `y`: Force applied to the player, where player movement is 15
+
+use bevy::prelude::*;
+
+use super::zone::zone_collider;
+use super::{Attribute, AttributeError, AttributeParams, ParamKind, ParamSpec};
+use crate::level::LevelId;
+
+#[derive(Component)]
+pub struct ForceZone(pub Vec2);
+
+impl Attribute for ForceZone {
+  const KEY: &'static str = "force";
+  const PARAMS: &'static [ParamSpec] = &[
+    ParamSpec::required("x", ParamKind::Float),
+    ParamSpec::required("y", ParamKind::Float),
+  ];
+
+  fn build(
+    commands: &mut Commands,
+    target: Entity,
+    _: LevelId,
+    position: Vec2,
+    params: AttributeParams,
+  ) -> Result<(), AttributeError> {
+    commands
+      .entity(target)
+      .insert(ForceZone(Vec2::new(params.float("x")?, params.float("y")?)))
+      .insert_bundle(zone_collider(position));
+
+    Ok(())
+  }
+}
@@ -0,0 +1,43 @@
+//! Solid sprite changing how much grip the player has while grounded on it,
+//! for ice or sticky surfaces. `friction(value)`
+//!
+//! # Usage
+//! `value`: Multiplier of the player's grip, below 1 is slippery and above 1 is
+//! sticky
+//!
+//! Applied by the player controller from the tile the hover ray lands on (see
+//! [crate::game::player]).
+
+use bevy::prelude::*;
+
+use super::solid::solid_collider;
+use super::{Attribute, AttributeError, AttributeParams, ParamKind, ParamSpec};
+use crate::level::LevelId;
+
+#[derive(Component)]
+pub struct Friction(pub f32);
+
+impl Attribute for Friction {
+  const KEY: &'static str = "friction";
+  const PARAMS: &'static [ParamSpec] = &[ParamSpec::required("value", ParamKind::Float)];
+
+  fn build(
+    commands: &mut Commands,
+    target: Entity,
+    _: LevelId,
+    position: Vec2,
+    params: AttributeParams,
+  ) -> Result<(), AttributeError> {
+    let value = params.float("value")?;
+    if value <= 0.0 {
+      return Err(AttributeError::invalid("value", "must be greater than zero"));
+    }
+
+    commands
+      .entity(target)
+      .insert(Friction(value))
+      .insert_bundle(solid_collider(position));
+
+    Ok(())
+  }
+}
@@ -1,66 +1,92 @@
-//! Gives a player the component provided within the parameter.
-//! `give(component_name(params...))`
+//! Gives the player an ability, built with the parameters provided.
+//! `give(attribute(params...))`
 
-use bevy::ecs::component::Component;
 use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
 
 use super::lex::ParseArgumentItem;
-use super::{Attribute, Dash, Player};
+use super::{Attribute, AttributeError, AttributeParams, AttributeRegistry, ParamKind, ParamSpec, Player};
 use crate::game::collision::{ContactQuery, ContactSubscription};
 use crate::game::collision_groups::*;
 use crate::level::LevelId;
 
+/// Ability to build onto the player when contacted.
 #[derive(Component)]
-pub struct Give(Vec<ParseArgumentItem>);
+pub struct Give {
+  key: String,
+  args: Vec<ParseArgumentItem>,
+  level: LevelId,
+  position: Vec2,
+}
 
 /// Typeless Give attribute, used to build [Give]
 pub struct GivableAttribute;
 
-impl Give {
-  pub fn build_component(&self) -> impl Component {
-    if let Some(ParseArgumentItem::Str(key)) = self.0.get(0) {
-      match key.as_str() {
-        Dash::KEY => Dash::default(),
-        _ => panic!("Attempted to use an invalid attribute when giving!"),
-      }
-    } else {
-      panic!("Attempted to use give attribute without component provided")
+/// Collider for pickups and pads that react on contact with the player.
+pub(super) fn pickup_collider(position: Vec2) -> ColliderBundle {
+  ColliderBundle {
+    position: position.into(),
+    shape: ColliderShape::cuboid(0.5, 0.5).into(),
+    material: ColliderMaterialComponent::default(),
+    flags: ColliderFlags {
+      collision_groups: DETECTS_PLAYER_GROUP,
+      solver_groups: NONE_GROUP,
+      active_events: ActiveEvents::CONTACT_EVENTS,
+      ..Default::default()
     }
+    .into(),
+    ..Default::default()
   }
 }
 
 impl Attribute for GivableAttribute {
   const KEY: &'static str = "give";
+  const PARAMS: &'static [ParamSpec] = &[ParamSpec::required("attribute", ParamKind::Ability)];
 
-  fn build(commands: &mut Commands, target: Entity, _: LevelId, position: Vec2, params: Vec<ParseArgumentItem>) {
-    let collider = ColliderBundle {
-      position: position.into(),
-      shape: ColliderShape::cuboid(0.5, 0.5).into(),
-      material: ColliderMaterialComponent::default(),
-      flags: ColliderFlags {
-        collision_groups: DETECTS_PLAYER_GROUP,
-        solver_groups: NONE_GROUP,
-        active_events: ActiveEvents::CONTACT_EVENTS,
-        ..Default::default()
-      }
-      .into(),
-      ..Default::default()
-    };
+  fn build(
+    commands: &mut Commands,
+    target: Entity,
+    level: LevelId,
+    position: Vec2,
+    params: AttributeParams,
+  ) -> Result<(), AttributeError> {
+    // The ability itself is checked against the registry before build
+    let (key, args) = params.ability("attribute")?;
 
     commands
       .entity(target)
-      .insert(Give(params))
+      .insert(Give {
+        key: key.to_string(),
+        args: args.to_vec(),
+        level,
+        position,
+      })
       .insert(ContactSubscription)
-      .insert_bundle(collider)
+      .insert_bundle(pickup_collider(position))
       .insert(ColliderPositionSync::Discrete);
+
+    Ok(())
   }
 }
 
-pub fn on_give_system(mut commands: Commands, player: Query<Entity, With<Player>>, goal: ContactQuery<Give>) {
+pub fn on_give_system(
+  mut commands: Commands,
+  player: Query<Entity, With<Player>>,
+  give: ContactQuery<Give>,
+  registry: Res<AttributeRegistry>,
+) {
   if let Ok(player) = player.get_single() {
-    goal.for_each(|(ent, give)| {
-      commands.entity(player).insert(give.build_component());
+    give.for_each(|(ent, give)| {
+      let built = registry
+        .resolve_ability(&give.key, give.args.clone())
+        .and_then(|(registration, params)| {
+          (registration.build)(&mut commands, player, give.level, give.position, params)
+        });
+
+      if let Err(err) = built {
+        error!(target: "on_give_system", "Failed to give `{}`: {}", give.key, err);
+      }
+
       commands.entity(ent).despawn();
     });
   }
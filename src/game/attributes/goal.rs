@@ -3,8 +3,7 @@
 use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
 
-use super::lex::ParseArgumentItem;
-use super::Attribute;
+use super::{Attribute, AttributeError, AttributeParams, ParamKind, ParamSpec};
 use crate::game::collision::{ContactQuery, ContactSubscription, PlayerContacted};
 use crate::game::collision_groups::*;
 use crate::level::next::NextLevel;
@@ -15,25 +14,18 @@ use crate::util::settings::{GameFile, LevelTransistionType};
 #[derive(Component)]
 pub struct Goal(pub usize);
 
-fn parse_params(params: Vec<ParseArgumentItem>) -> usize {
-  let arg = params
-    .get(0)
-    .unwrap_or_else(|| panic!("Goal attribute created with no exit number parameter!"));
-  if let ParseArgumentItem::Number(exit) = arg {
-    exit
-      .clone()
-      .try_into()
-      .unwrap_or_else(|_| panic!("Invalid number provided for exit number!"))
-  } else {
-    panic!("Non-number parameter provided to goal attribute!");
-  }
-}
-
 impl Attribute for Goal {
   const KEY: &'static str = "goal";
+  const PARAMS: &'static [ParamSpec] = &[ParamSpec::required("exit", ParamKind::Number)];
 
-  fn build(commands: &mut Commands, target: Entity, _: LevelId, position: Vec2, params: Vec<ParseArgumentItem>) {
-    let exit_number = parse_params(params);
+  fn build(
+    commands: &mut Commands,
+    target: Entity,
+    _: LevelId,
+    position: Vec2,
+    params: AttributeParams,
+  ) -> Result<(), AttributeError> {
+    let exit_number = params.unsigned("exit")? as usize;
 
     let collider = ColliderBundle {
       position: position.into(),
@@ -55,6 +47,8 @@ impl Attribute for Goal {
       .insert(ContactSubscription)
       .insert_bundle(collider)
       .insert(ColliderPositionSync::Discrete);
+
+    Ok(())
   }
 }
 
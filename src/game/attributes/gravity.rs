@@ -0,0 +1,35 @@
+//! Scales gravity on the player while they are inside the sprite.
+//! `gravity(scale)`
+//!
+//! # Usage
+//! `scale`: Multiplier of the player's gravity. Below zero lifts the player
+//! instead.
+
+use bevy::prelude::*;
+
+use super::zone::zone_collider;
+use super::{Attribute, AttributeError, AttributeParams, ParamKind, ParamSpec};
+use crate::level::LevelId;
+
+#[derive(Component)]
+pub struct GravityZone(pub f32);
+
+impl Attribute for GravityZone {
+  const KEY: &'static str = "gravity";
+  const PARAMS: &'static [ParamSpec] = &[ParamSpec::required("scale", ParamKind::Float)];
+
+  fn build(
+    commands: &mut Commands,
+    target: Entity,
+    _: LevelId,
+    position: Vec2,
+    params: AttributeParams,
+  ) -> Result<(), AttributeError> {
+    commands
+      .entity(target)
+      .insert(GravityZone(params.float("scale")?))
+      .insert_bundle(zone_collider(position));
+
+    Ok(())
+  }
+}
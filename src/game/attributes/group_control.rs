@@ -0,0 +1,164 @@
+//! Pauses or reverses a movement group while a signal channel is active.
+//! `group_control(group, channel, action, invert)`
+//!
+//! # Usage
+//! `group`: [MovementGroup](super::MovementGroup) id to control
+//!
+//! `channel`: Signal channel that controls the group
+//!
+//! `action`: `pause` or `reverse` the group while the channel is active
+//! (default pause)
+//!
+//! `invert`: Act while the channel is inactive instead (default false)
+//!
+//! Use at most one control per group and action.
+
+use bevy::prelude::*;
+
+use super::{Attribute, AttributeError, AttributeParams, MovementGroupCommands, MovementGroups, ParamKind, ParamSpec};
+use crate::game::signal::{SignalChannel, Signals};
+use crate::level::LevelId;
+
+/// What a [GroupControl] does to its group.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GroupAction {
+  Pause,
+  Reverse,
+}
+
+impl GroupAction {
+  pub fn from_param(value: &str) -> Option<Self> {
+    match value {
+      "pause" => Some(Self::Pause),
+      "reverse" => Some(Self::Reverse),
+      _ => None,
+    }
+  }
+}
+
+#[derive(Component)]
+pub struct GroupControl {
+  pub group: u32,
+  pub channel: SignalChannel,
+  pub action: GroupAction,
+  pub invert: bool,
+}
+
+impl Attribute for GroupControl {
+  const KEY: &'static str = "group_control";
+  const PARAMS: &'static [ParamSpec] = &[
+    ParamSpec::required("group", ParamKind::Number),
+    ParamSpec::required("channel", ParamKind::Number),
+    ParamSpec::string("action", "pause"),
+    ParamSpec::boolean("invert", false),
+  ];
+
+  fn build(
+    commands: &mut Commands,
+    target: Entity,
+    _: LevelId,
+    _: Vec2,
+    params: AttributeParams,
+  ) -> Result<(), AttributeError> {
+    let action = GroupAction::from_param(params.str("action")?)
+      .ok_or_else(|| AttributeError::invalid("action", "must be one of pause or reverse"))?;
+
+    commands.entity(target).insert(GroupControl {
+      group: params.unsigned("group")?,
+      channel: params.unsigned("channel")?,
+      action,
+      invert: params.bool("invert")?,
+    });
+
+    Ok(())
+  }
+}
+
+/// Queues [MovementGroupCommands] to bring each controlled group's clock in
+/// line with its channel.
+pub fn group_control_system(
+  signals: Res<Signals>,
+  groups: Res<MovementGroups>,
+  mut group_commands: ResMut<MovementGroupCommands>,
+  controls: Query<&GroupControl>,
+) {
+  controls.for_each(|control| {
+    let active = signals.active(control.channel) != control.invert;
+    let clock = groups.clock(control.group);
+    match control.action {
+      GroupAction::Pause if active != clock.paused => {
+        if active {
+          group_commands.pause(control.group);
+        } else {
+          group_commands.resume(control.group);
+        }
+      },
+      GroupAction::Reverse if active != clock.reversed => group_commands.reverse(control.group),
+      _ => {},
+    }
+  });
+}
+
+#[cfg(test)]
+mod tests {
+  use bevy::prelude::*;
+
+  use crate::game::attributes::*;
+  use crate::game::signal::{handle_signal_commands, SignalCommands, SignalSystemSteps, Signals};
+
+  #[test]
+  fn test_group_control() {
+    let mut world = World::default();
+    let mut update_stage = SystemStage::single_threaded();
+
+    world.insert_resource(Signals::default());
+    world.insert_resource(SignalCommands::default());
+    world.insert_resource(MovementGroups::default());
+    world.insert_resource(MovementGroupCommands::default());
+    update_stage.add_system(handle_signal_commands.label(SignalSystemSteps::ApplyCommands));
+    update_stage.add_system(
+      group_control_system
+        .after(SignalSystemSteps::ApplyCommands)
+        .before(MovingAttributeSystemSteps::ApplyGroupCommands),
+    );
+    update_stage.add_system(handle_movement_group_commands.label(MovingAttributeSystemSteps::ApplyGroupCommands));
+
+    world.spawn().insert(GroupControl {
+      group: 1,
+      channel: 2,
+      action: GroupAction::Pause,
+      invert: false,
+    });
+    world.spawn().insert(GroupControl {
+      group: 1,
+      channel: 3,
+      action: GroupAction::Reverse,
+      invert: false,
+    });
+
+    update_stage.run(&mut world);
+    assert_eq!(
+      world.get_resource::<MovementGroups>().unwrap().clock(1),
+      GroupClock::default()
+    );
+
+    // Channels pause and reverse the group while active
+
+    let mut signal_commands = world.get_resource_mut::<SignalCommands>().unwrap();
+    signal_commands.set(2, true);
+    signal_commands.set(3, true);
+    update_stage.run(&mut world);
+    update_stage.run(&mut world);
+
+    let clock = world.get_resource::<MovementGroups>().unwrap().clock(1);
+    assert!(clock.paused);
+    assert!(clock.reversed);
+
+    world.get_resource_mut::<SignalCommands>().unwrap().set(2, false);
+    update_stage.run(&mut world);
+
+    let clock = world.get_resource::<MovementGroups>().unwrap().clock(1);
+    assert!(!clock.paused);
+    assert!(clock.reversed);
+  }
+}
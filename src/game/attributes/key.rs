@@ -0,0 +1,255 @@
+//! Key that follows the player once picked up, opening a matching `lock`.
+//! `key(color)`
+//!
+//! # Usage
+//! `color`: Name matching the key to its locks
+//!
+//! Keys picked up, and locks opened, since the last checkpoint are returned if
+//! the player dies.
+
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use super::give::pickup_collider;
+use super::{Attribute, AttributeError, AttributeParams, Lock, ParamKind, ParamSpec, Player};
+use crate::game::collision::{ContactSubscription, PlayerContacted};
+use crate::game::collision_groups::*;
+use crate::game::orbs::follow_tick;
+use crate::level::config::SPRITE_SIZE;
+use crate::level::LevelId;
+
+/// Pixels a held key trails behind the player, and behind the key before it.
+const KEY_SPACING: f32 = SPRITE_SIZE as f32 * 0.75;
+/// Follow distance and acceleration of held keys, see [follow_tick].
+const KEY_FOLLOW_DIST: f32 = 12.0;
+const KEY_FOLLOW_ACCEL: f32 = 24.0;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum KeyState {
+  /// Waiting to be picked up.
+  Waiting,
+  /// Following the player. Committed once a checkpoint is reached.
+  Held { committed: bool },
+  /// Used on a lock since the last checkpoint. Returns to the player on
+  /// death if the key was committed, otherwise returns to where it started.
+  Used { committed: bool },
+  /// Used on a lock before the last checkpoint.
+  Spent,
+}
+
+#[derive(Component)]
+pub struct Key {
+  pub color: String,
+  pub state: KeyState,
+  starting_position: Vec2,
+}
+
+impl Key {
+  pub fn held(&self) -> bool {
+    matches!(self.state, KeyState::Held { .. })
+  }
+
+  /// Keeps the key through later deaths.
+  pub fn commit(&mut self) {
+    self.state = match self.state {
+      KeyState::Held { .. } => KeyState::Held { committed: true },
+      KeyState::Used { .. } => KeyState::Spent,
+      state => state,
+    };
+  }
+
+  /// Undoes anything that happened to the key since the last checkpoint.
+  pub fn revert(&mut self) {
+    self.state = match self.state {
+      KeyState::Held { committed: false } | KeyState::Used { committed: false } => KeyState::Waiting,
+      KeyState::Used { committed: true } => KeyState::Held { committed: true },
+      state => state,
+    };
+  }
+}
+
+impl Attribute for Key {
+  const KEY: &'static str = "key";
+  const PARAMS: &'static [ParamSpec] = &[ParamSpec::required("color", ParamKind::Str)];
+
+  fn build(
+    commands: &mut Commands,
+    target: Entity,
+    _: LevelId,
+    position: Vec2,
+    params: AttributeParams,
+  ) -> Result<(), AttributeError> {
+    commands
+      .entity(target)
+      .insert(Key {
+        color: params.str("color")?.to_string(),
+        state: KeyState::Waiting,
+        starting_position: position,
+      })
+      .insert(ContactSubscription)
+      .insert_bundle(pickup_collider(position))
+      .insert(ColliderPositionSync::Discrete);
+
+    Ok(())
+  }
+}
+
+pub enum KeyCommand {
+  /// Keeps keys and locks as they are, as a checkpoint was reached
+  Commit,
+  /// Returns keys and locks to how they were at the last checkpoint
+  Revert,
+}
+
+/// A command queue to commit or revert [Key]s and [Lock]s.
+#[derive(Default)]
+pub struct KeyCommands {
+  queue: VecDeque<KeyCommand>,
+}
+
+impl KeyCommands {
+  pub fn commit(&mut self) {
+    self.queue.push_back(KeyCommand::Commit);
+  }
+
+  pub fn revert(&mut self) {
+    self.queue.push_back(KeyCommand::Revert);
+  }
+
+  pub fn pop(&mut self) -> Option<KeyCommand> {
+    self.queue.pop_front()
+  }
+}
+
+/// Consumes [PlayerContacted] tags, picking up keys. Held keys are moved by
+/// their [Transform] rather than their collider.
+pub fn on_key_system(
+  mut commands: Commands,
+  mut key: Query<(Entity, &mut Key, &mut ColliderFlagsComponent), With<PlayerContacted>>,
+) {
+  key.for_each_mut(|(ent, mut key, mut flags)| {
+    if key.state == KeyState::Waiting {
+      key.state = KeyState::Held { committed: false };
+      flags.collision_groups = NONE_GROUP;
+      commands.entity(ent).remove::<ColliderPositionSync>();
+    }
+
+    commands.entity(ent).remove::<PlayerContacted>();
+  });
+}
+
+/// Trails held keys behind the player.
+pub fn key_follow_system(
+  time: Res<Time>,
+  player: Query<&Transform, (With<Player>, Without<Key>)>,
+  mut keys: Query<(&Key, &mut Transform), Without<Player>>,
+) {
+  if let Ok(player_trans) = player.get_single() {
+    keys
+      .iter_mut()
+      .filter(|(key, _)| key.held())
+      .enumerate()
+      .for_each(|(i, (_, mut trans))| {
+        let target = player_trans.translation.truncate() + Vec2::new(-KEY_SPACING * (i + 1) as f32, KEY_SPACING);
+        trans.translation = follow_tick(
+          &trans.translation.truncate(),
+          &target,
+          KEY_FOLLOW_DIST,
+          KEY_FOLLOW_ACCEL,
+          &time,
+        )
+        .extend(trans.translation.z);
+      });
+  }
+}
+
+/// Consumes [KeyCommands], returning reverted keys to the player or to where
+/// they started.
+pub fn handle_key_commands(
+  mut commands: Commands,
+  mut key_commands: ResMut<KeyCommands>,
+  mut keys: Query<
+    (
+      Entity,
+      &mut Key,
+      &mut ColliderFlagsComponent,
+      &mut Transform,
+      &mut Visibility,
+    ),
+    Without<Lock>,
+  >,
+  mut locks: Query<(&mut Lock, &mut ColliderFlagsComponent, &mut Visibility), Without<Key>>,
+) {
+  while let Some(command) = key_commands.pop() {
+    match command {
+      KeyCommand::Commit => {
+        keys.for_each_mut(|(_, mut key, _, _, _)| key.commit());
+        locks.for_each_mut(|(mut lock, _, _)| lock.commit());
+      },
+      KeyCommand::Revert => {
+        keys.for_each_mut(|(ent, mut key, mut flags, mut trans, mut visibility)| {
+          let previous = key.state;
+          key.revert();
+          if key.state == previous {
+            return;
+          }
+
+          visibility.is_visible = true;
+          if key.state == KeyState::Waiting {
+            flags.collision_groups = DETECTS_PLAYER_GROUP;
+            trans.translation = (key.starting_position * SPRITE_SIZE as f32).extend(trans.translation.z);
+            commands.entity(ent).insert(ColliderPositionSync::Discrete);
+          }
+        });
+        locks.for_each_mut(|(mut lock, mut flags, mut visibility)| {
+          if lock.revert() {
+            lock.apply(&mut flags, &mut visibility);
+          }
+        });
+      },
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use bevy::prelude::*;
+
+  use crate::game::attributes::*;
+
+  fn key() -> Key {
+    Key {
+      color: "red".to_string(),
+      state: KeyState::Waiting,
+      starting_position: Vec2::ZERO,
+    }
+  }
+
+  #[test]
+  fn test_key_state() {
+    // Keys picked up since the last checkpoint are lost
+    let mut lost = key();
+    lost.state = KeyState::Held { committed: false };
+    lost.revert();
+    assert_eq!(lost.state, KeyState::Waiting);
+
+    // Committed keys are kept, and return to the player if used since
+    let mut kept = key();
+    kept.state = KeyState::Held { committed: false };
+    kept.commit();
+    kept.revert();
+    assert_eq!(kept.state, KeyState::Held { committed: true });
+
+    kept.state = KeyState::Used { committed: true };
+    kept.revert();
+    assert_eq!(kept.state, KeyState::Held { committed: true });
+
+    // Keys used before the last checkpoint stay used
+    kept.state = KeyState::Used { committed: true };
+    kept.commit();
+    kept.revert();
+    assert_eq!(kept.state, KeyState::Spent);
+  }
+}
@@ -1,158 +1,362 @@
-#[derive(Clone, Debug, PartialEq, Eq)]
+//! Tokenizer and parser for attribute strings.
+//!
+//! Attribute strings take the form `key(arg, ...)`. Arguments may be integers
+//! (`5`, `-2`), floats (`0.5`), booleans, bare words (`right`), quoted strings
+//! (`"hello, world"`) or nested calls (`dash(2)`). Arguments may also be given
+//! by name (`moving(dir=right, distance=5)`), after any positional arguments.
+//! Errors carry the byte [Span] of the offending text, so editors and
+//! validators can point at it.
+
+use std::fmt;
+use std::iter::Peekable;
+use std::ops::Range;
+use std::str::CharIndices;
+
+/// Byte range within an attribute string.
+pub type Span = Range<usize>;
+
+#[derive(Clone, Debug, PartialEq)]
 pub enum ParseArgumentItem {
   Str(String),
   Number(i64),
+  Float(f32),
   Bool(bool),
+  /// Nested call, such as `dash(2)` within `give(dash(2))`
+  Call(String, Vec<ParseArgumentItem>),
+  /// Named argument, such as `distance=5`
+  Keyword(String, Box<ParseArgumentItem>),
+  /// Values collected by a list parameter, see
+  /// [ParamKind::List](super::ParamKind::List). Never produced by the parser.
+  List(Vec<ParseArgumentItem>),
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
-pub enum ParseItem {
-  Key(String),
-  Argument(ParseArgumentItem),
+#[derive(Clone, Debug, PartialEq)]
+pub enum TokenKind {
+  Ident(String),
+  Number(i64),
+  Float(f32),
+  Str(String),
+  OpenParen,
+  CloseParen,
+  Comma,
+  Equals,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
-pub enum LexItem {
-  Paren,
-  Comma,
-  Space,
-  Minus,
-  Number(u8),
-  Character(char),
+#[derive(Clone, Debug, PartialEq)]
+pub struct Token {
+  pub kind: TokenKind,
+  pub span: Span,
+}
+
+impl Token {
+  fn new(kind: TokenKind, span: Span) -> Self {
+    Self { kind, span }
+  }
 }
 
-fn lex(input: &String) -> Vec<LexItem> {
-  input
-    .chars()
-    .into_iter()
-    .map(|c| match c {
-      '-' => LexItem::Minus,
-      ' ' => LexItem::Space,
-      ',' => LexItem::Comma,
-      '(' | ')' => LexItem::Paren,
-      '0'..='9' => LexItem::Number(c.to_string().parse::<u8>().unwrap()),
-      c => LexItem::Character(c),
-    })
-    .collect()
+/// Error found while lexing or parsing an attribute string.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseError {
+  pub message: String,
+  pub span: Span,
 }
 
-fn parse_string(input: &Vec<LexItem>, current_pos: usize) -> Option<(usize, String)> {
-  let mut pos = current_pos;
-  let mut char_list = Vec::new();
-
-  while let Some(item) = input.get(pos) {
-    if let LexItem::Character(n) = item {
-      char_list.push(n.to_string());
-      pos += 1;
-    } else if item == &LexItem::Minus {
-      char_list.push('-'.to_string());
-      pos += 1;
-    } else if let LexItem::Number(n) = item {
-      char_list.push(n.to_string());
-      pos += 1;
-    } else {
-      break;
+impl ParseError {
+  fn new<S: ToString>(message: S, span: Span) -> Self {
+    Self {
+      message: message.to_string(),
+      span,
     }
   }
 
-  Some((pos, char_list.join("")))
+  /// Renders the source string with the error's span underlined.
+  pub fn annotate(&self, source: &str) -> String {
+    let start = self.span.start.min(source.len());
+    let end = self.span.end.min(source.len());
+    let padding = source[..start].chars().count();
+    let width = source[start..end].chars().count().max(1);
+    format!("{}\n{}{}", source, " ".repeat(padding), "^".repeat(width))
+  }
 }
 
-fn parse_number(input: &Vec<LexItem>, current_pos: usize) -> Option<(usize, i64)> {
-  let mut pos = current_pos;
-  let mut number_list = Vec::new();
+impl fmt::Display for ParseError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{} at {}..{}", self.message, self.span.start, self.span.end)
+  }
+}
 
-  while let Some(item) = input.get(pos) {
-    if let LexItem::Number(n) = item {
-      number_list.push(n.to_string());
-      pos += 1;
-    } else {
+type CharStream<'a> = Peekable<CharIndices<'a>>;
+
+/// Consumes characters while `pred` holds, returning the end of the consumed
+/// range.
+fn take_while<F: Fn(char) -> bool>(chars: &mut CharStream, mut end: usize, pred: F) -> usize {
+  while let Some(&(i, c)) = chars.peek() {
+    if !pred(c) {
       break;
     }
+    end = i + c.len_utf8();
+    chars.next();
+  }
+  end
+}
+
+fn lex_number(input: &str, chars: &mut CharStream, start: usize) -> Result<Token, ParseError> {
+  let mut end = start;
+  if let Some(&(i, '-')) = chars.peek() {
+    end = i + 1;
+    chars.next();
+  }
+
+  let digits_start = end;
+  end = take_while(chars, end, |c| c.is_ascii_digit());
+  if end == digits_start {
+    return Err(ParseError::new("expected a digit after `-`", start..end));
+  }
+
+  let mut is_float = false;
+  if let Some(&(i, '.')) = chars.peek() {
+    chars.next();
+    is_float = true;
+    let fraction_start = i + 1;
+    end = take_while(chars, fraction_start, |c| c.is_ascii_digit());
+    if end == fraction_start {
+      return Err(ParseError::new("expected a digit after `.`", start..end));
+    }
   }
 
-  if let Ok(number) = number_list.join("").parse::<i64>() {
-    Some((pos, number))
+  let text = &input[start..end];
+  let kind = if is_float {
+    text.parse::<f32>().map(TokenKind::Float).ok()
   } else {
-    None
+    text.parse::<i64>().map(TokenKind::Number).ok()
+  };
+
+  kind
+    .map(|kind| Token::new(kind, start..end))
+    .ok_or_else(|| ParseError::new(format!("invalid number `{}`", text), start..end))
+}
+
+fn lex_string(chars: &mut CharStream, start: usize) -> Result<Token, ParseError> {
+  // Opening quote
+  chars.next();
+
+  let mut value = String::new();
+  while let Some((i, c)) = chars.next() {
+    match c {
+      '"' => return Ok(Token::new(TokenKind::Str(value), start..i + 1)),
+      '\\' => match chars.next() {
+        Some((_, '"')) => value.push('"'),
+        Some((_, '\\')) => value.push('\\'),
+        Some((_, 'n')) => value.push('\n'),
+        Some((j, other)) => {
+          return Err(ParseError::new(
+            format!("unknown escape `\\{}`", other),
+            i..j + other.len_utf8(),
+          ))
+        },
+        None => break,
+      },
+      c => value.push(c),
+    }
   }
+
+  Err(ParseError::new("unterminated string", start..start + 1))
 }
 
-fn parse(input: Vec<LexItem>) -> Result<Vec<ParseItem>, String> {
-  let mut result = Vec::new();
-  let mut pos = 0;
-  let mut within_arguments = false;
-  while let Some(item) = input.get(pos) {
-    match item {
-      LexItem::Paren => {
-        pos += 1;
-        within_arguments = true;
+fn is_ident_start(c: char) -> bool {
+  c.is_alphabetic() || c == '_'
+}
+
+fn is_ident_continue(c: char) -> bool {
+  c.is_alphanumeric() || c == '_' || c == '-'
+}
+
+/// Splits an attribute string into [Token]s.
+pub fn lex(input: &str) -> Result<Vec<Token>, ParseError> {
+  let mut tokens = Vec::new();
+  let mut chars = input.char_indices().peekable();
+
+  while let Some(&(start, c)) = chars.peek() {
+    let single = |kind| Token::new(kind, start..start + 1);
+    match c {
+      c if c.is_whitespace() => {
+        chars.next();
       },
-      LexItem::Comma => {
-        pos += 1;
+      '(' => {
+        chars.next();
+        tokens.push(single(TokenKind::OpenParen));
       },
-      LexItem::Space => {
-        pos += 1;
+      ')' => {
+        chars.next();
+        tokens.push(single(TokenKind::CloseParen));
       },
-      // Negative Number
-      LexItem::Minus => {
-        if let Some((new_pos, n)) = parse_number(&input, pos + 1) {
-          pos = new_pos;
-          result.push(ParseItem::Argument(ParseArgumentItem::Number(-n)));
-        } else {
-          return Err(format!("Failed to parse Minus at pos {}", pos));
-        }
+      ',' => {
+        chars.next();
+        tokens.push(single(TokenKind::Comma));
       },
-      LexItem::Number(_) => {
-        if let Some((new_pos, n)) = parse_number(&input, pos) {
-          pos = new_pos;
-          result.push(ParseItem::Argument(ParseArgumentItem::Number(n)));
-        }
+      '=' => {
+        chars.next();
+        tokens.push(single(TokenKind::Equals));
       },
-      LexItem::Character(_) => {
-        if let Some((new_pos, item)) = parse_string(&input, pos) {
-          pos = new_pos;
-          if let Ok(b) = item.parse::<bool>() {
-            result.push(ParseItem::Argument(ParseArgumentItem::Bool(b)));
-          } else if within_arguments {
-            result.push(ParseItem::Argument(ParseArgumentItem::Str(item)));
-          } else {
-            result.push(ParseItem::Key(item));
-          }
-        }
+      '"' => tokens.push(lex_string(&mut chars, start)?),
+      '-' | '0'..='9' => tokens.push(lex_number(input, &mut chars, start)?),
+      c if is_ident_start(c) => {
+        let end = take_while(&mut chars, start, is_ident_continue);
+        tokens.push(Token::new(TokenKind::Ident(input[start..end].to_string()), start..end));
+      },
+      c => {
+        return Err(ParseError::new(
+          format!("unexpected character `{}`", c),
+          start..start + c.len_utf8(),
+        ))
       },
     }
   }
 
-  Ok(result)
+  Ok(tokens)
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
-pub struct AttributeEntry(pub String, pub Vec<ParseArgumentItem>);
+/// Recursive descent parser over a list of [Token]s.
+struct Parser {
+  tokens: Vec<Token>,
+  pos: usize,
+  input_len: usize,
+}
+
+impl Parser {
+  fn peek(&self) -> Option<&Token> {
+    self.tokens.get(self.pos)
+  }
+
+  fn peek_kind(&self, offset: usize) -> Option<&TokenKind> {
+    self.tokens.get(self.pos + offset).map(|token| &token.kind)
+  }
 
-impl From<String> for AttributeEntry {
-  fn from(entry: String) -> Self {
-    let tokens = lex(&entry);
-    match parse(tokens) {
-      Ok(items) => {
-        if let Some(ParseItem::Key(key)) = items.get(0) {
-          let args: Vec<_> = Vec::from_iter(items[1..].iter().cloned())
-            .iter()
-            .map(|x| {
-              if let ParseItem::Argument(arg) = x {
-                arg.clone()
-              } else {
-                panic!("Found second key in entry {}", entry);
-              }
-            })
-            .collect();
-          AttributeEntry(key.clone(), args)
+  fn next(&mut self) -> Option<Token> {
+    let token = self.tokens.get(self.pos).cloned();
+    self.pos += 1;
+    token
+  }
+
+  fn end_span(&self) -> Span {
+    self.input_len..self.input_len
+  }
+
+  /// Parses `(arg, ...)`. Expects the next token to be an opening paren.
+  fn parse_call_arguments(&mut self) -> Result<Vec<ParseArgumentItem>, ParseError> {
+    let open = self.next().map(|token| token.span).unwrap_or_else(|| self.end_span());
+    let mut args = Vec::new();
+    let mut keyword_seen = false;
+
+    loop {
+      match self.peek().map(|token| &token.kind) {
+        Some(TokenKind::CloseParen) => {
+          self.next();
+          return Ok(args);
+        },
+        None => return Err(ParseError::new("unclosed `(`", open)),
+        _ => {},
+      }
+
+      let arg_start = self.peek().map(|token| token.span.start).unwrap_or(self.input_len);
+      let arg = self.parse_argument()?;
+      if let ParseArgumentItem::Keyword(_, _) = arg {
+        keyword_seen = true;
+      } else if keyword_seen {
+        let arg_end = self.tokens[self.pos - 1].span.end;
+        return Err(ParseError::new(
+          "positional argument after keyword argument",
+          arg_start..arg_end,
+        ));
+      }
+      args.push(arg);
+
+      match self.next() {
+        Some(Token {
+          kind: TokenKind::Comma, ..
+        }) => {},
+        Some(Token {
+          kind: TokenKind::CloseParen,
+          ..
+        }) => return Ok(args),
+        Some(token) => return Err(ParseError::new("expected `,` or `)`", token.span)),
+        None => return Err(ParseError::new("unclosed `(`", open)),
+      }
+    }
+  }
+
+  /// Parses either `name=value` or a positional value.
+  fn parse_argument(&mut self) -> Result<ParseArgumentItem, ParseError> {
+    if let (Some(TokenKind::Ident(name)), Some(TokenKind::Equals)) = (self.peek_kind(0), self.peek_kind(1)) {
+      let name = name.clone();
+      self.pos += 2;
+      return Ok(ParseArgumentItem::Keyword(name, Box::new(self.parse_value()?)));
+    }
+
+    self.parse_value()
+  }
+
+  fn parse_value(&mut self) -> Result<ParseArgumentItem, ParseError> {
+    let token = self
+      .next()
+      .ok_or_else(|| ParseError::new("expected an argument", self.end_span()))?;
+
+    match token.kind {
+      TokenKind::Ident(name) => {
+        if let Some(TokenKind::OpenParen) = self.peek().map(|token| &token.kind) {
+          Ok(ParseArgumentItem::Call(name, self.parse_call_arguments()?))
+        } else if let Ok(b) = name.parse::<bool>() {
+          Ok(ParseArgumentItem::Bool(b))
         } else {
-          panic!("Failed to find key in attribute {}", entry);
+          Ok(ParseArgumentItem::Str(name))
         }
       },
-      Err(err) => panic!("{}", err),
+      TokenKind::Number(n) => Ok(ParseArgumentItem::Number(n)),
+      TokenKind::Float(f) => Ok(ParseArgumentItem::Float(f)),
+      TokenKind::Str(s) => Ok(ParseArgumentItem::Str(s)),
+      _ => Err(ParseError::new("expected an argument", token.span)),
+    }
+  }
+
+  fn parse_entry(&mut self) -> Result<AttributeEntry, ParseError> {
+    let key = match self.next() {
+      Some(Token {
+        kind: TokenKind::Ident(key),
+        ..
+      }) => key,
+      Some(token) => return Err(ParseError::new("expected an attribute name", token.span)),
+      None => return Err(ParseError::new("expected an attribute name", self.end_span())),
+    };
+
+    let args = if let Some(TokenKind::OpenParen) = self.peek().map(|token| &token.kind) {
+      self.parse_call_arguments()?
+    } else {
+      Vec::new()
+    };
+
+    if let Some(token) = self.peek() {
+      return Err(ParseError::new(
+        "unexpected input after attribute",
+        token.span.start..self.input_len,
+      ));
+    }
+
+    Ok(AttributeEntry(key, args))
+  }
+}
+
+/// Parsed attribute, as its key and arguments.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AttributeEntry(pub String, pub Vec<ParseArgumentItem>);
+
+impl AttributeEntry {
+  pub fn parse(input: &str) -> Result<Self, ParseError> {
+    Parser {
+      tokens: lex(input)?,
+      pos: 0,
+      input_len: input.len(),
     }
+    .parse_entry()
   }
 }
 
@@ -160,210 +364,164 @@ impl From<String> for AttributeEntry {
 mod tests {
   use crate::game::attributes::lex::*;
 
+  fn kinds(input: &str) -> Vec<TokenKind> {
+    lex(input).unwrap().into_iter().map(|token| token.kind).collect()
+  }
+
   #[test]
   fn test_lex() {
     assert_eq!(
-      lex(&"d(0,9)".to_string()),
+      kinds("d(0,19)"),
       vec![
-        LexItem::Character('d'),
-        LexItem::Paren,
-        LexItem::Number(0),
-        LexItem::Comma,
-        LexItem::Number(9),
-        LexItem::Paren
+        TokenKind::Ident("d".to_string()),
+        TokenKind::OpenParen,
+        TokenKind::Number(0),
+        TokenKind::Comma,
+        TokenKind::Number(19),
+        TokenKind::CloseParen
       ]
     );
     assert_eq!(
-      lex(&"d(1, 2)".to_string()),
+      kinds("d(-1, 2.5)"),
       vec![
-        LexItem::Character('d'),
-        LexItem::Paren,
-        LexItem::Number(1),
-        LexItem::Comma,
-        LexItem::Space,
-        LexItem::Number(2),
-        LexItem::Paren
+        TokenKind::Ident("d".to_string()),
+        TokenKind::OpenParen,
+        TokenKind::Number(-1),
+        TokenKind::Comma,
+        TokenKind::Float(2.5),
+        TokenKind::CloseParen
       ]
     );
     assert_eq!(
-      lex(&"d(-1, 2)".to_string()),
+      kinds("sign(\"hi, \\\"you\\\"\")"),
       vec![
-        LexItem::Character('d'),
-        LexItem::Paren,
-        LexItem::Minus,
-        LexItem::Number(1),
-        LexItem::Comma,
-        LexItem::Space,
-        LexItem::Number(2),
-        LexItem::Paren
+        TokenKind::Ident("sign".to_string()),
+        TokenKind::OpenParen,
+        TokenKind::Str("hi, \"you\"".to_string()),
+        TokenKind::CloseParen
       ]
     );
   }
 
   #[test]
-  fn test_parse_number() {
-    assert_eq!(
-      parse_number(&vec![LexItem::Number(1), LexItem::Number(2)], 0),
-      Some((2, 12))
-    );
-    assert_eq!(
-      parse_number(&vec![LexItem::Number(1), LexItem::Number(2), LexItem::Paren], 0),
-      Some((2, 12))
-    );
-    assert_eq!(
-      parse_number(&vec![LexItem::Number(1), LexItem::Paren, LexItem::Number(2)], 0),
-      Some((1, 1))
-    );
+  fn test_lex_spans() {
+    let spans: Vec<_> = lex("ab( -12 ,x)")
+      .unwrap()
+      .into_iter()
+      .map(|token| token.span)
+      .collect();
+    assert_eq!(spans, vec![0..2, 2..3, 4..7, 8..9, 9..10, 10..11]);
   }
 
   #[test]
-  fn test_parse_string() {
-    assert_eq!(
-      parse_string(&vec![LexItem::Character('a'), LexItem::Character('b')], 0),
-      Some((2, "ab".to_string()))
-    );
-    assert_eq!(
-      parse_string(
-        &vec![LexItem::Character('a'), LexItem::Paren, LexItem::Character('b')],
-        0
-      ),
-      Some((1, "a".to_string()))
-    );
+  fn test_lex_errors() {
+    assert_eq!(lex("d(1.)").unwrap_err().span, 2..4);
+    assert_eq!(lex("d(-)").unwrap_err().span, 2..3);
+    assert_eq!(lex("d(\"open)").unwrap_err().span, 2..3);
+    assert_eq!(lex("d(1;2)").unwrap_err().span, 3..4);
   }
 
   #[test]
   fn test_parse() {
     assert_eq!(
-      parse(vec![
-        LexItem::Character('a'),
-        LexItem::Paren,
-        LexItem::Number(1),
-        LexItem::Comma,
-        LexItem::Number(2),
-        LexItem::Paren
-      ]),
-      Ok(vec![
-        ParseItem::Key("a".to_string()),
-        ParseItem::Argument(ParseArgumentItem::Number(1)),
-        ParseItem::Argument(ParseArgumentItem::Number(2))
-      ])
-    );
-
-    assert_eq!(
-      parse(vec![
-        LexItem::Character('a'),
-        LexItem::Paren,
-        LexItem::Number(1),
-        LexItem::Number(1),
-        LexItem::Comma,
-        LexItem::Number(2),
-        LexItem::Paren
-      ]),
-      Ok(vec![
-        ParseItem::Key("a".to_string()),
-        ParseItem::Argument(ParseArgumentItem::Number(11)),
-        ParseItem::Argument(ParseArgumentItem::Number(2))
-      ])
+      AttributeEntry::parse("d(1,2,test,true)"),
+      Ok(AttributeEntry(
+        "d".to_string(),
+        vec![
+          ParseArgumentItem::Number(1),
+          ParseArgumentItem::Number(2),
+          ParseArgumentItem::Str("test".to_string()),
+          ParseArgumentItem::Bool(true)
+        ]
+      ))
     );
-
+    assert_eq!(AttributeEntry::parse("d"), Ok(AttributeEntry("d".to_string(), vec![])));
     assert_eq!(
-      parse(vec![
-        LexItem::Character('a'),
-        LexItem::Paren,
-        LexItem::Number(1),
-        LexItem::Number(1),
-        LexItem::Comma,
-        LexItem::Character('b'),
-        LexItem::Character('a'),
-        LexItem::Paren
-      ]),
-      Ok(vec![
-        ParseItem::Key("a".to_string()),
-        ParseItem::Argument(ParseArgumentItem::Number(11)),
-        ParseItem::Argument(ParseArgumentItem::Str("ba".to_string()))
-      ])
+      AttributeEntry::parse("d()"),
+      Ok(AttributeEntry("d".to_string(), vec![]))
     );
-
     assert_eq!(
-      parse(vec![
-        LexItem::Character('a'),
-        LexItem::Paren,
-        LexItem::Minus,
-        LexItem::Number(1),
-        LexItem::Number(1),
-        LexItem::Comma,
-        LexItem::Character('t'),
-        LexItem::Character('r'),
-        LexItem::Character('u'),
-        LexItem::Character('e'),
-        LexItem::Paren
-      ]),
-      Ok(vec![
-        ParseItem::Key("a".to_string()),
-        ParseItem::Argument(ParseArgumentItem::Number(-11)),
-        ParseItem::Argument(ParseArgumentItem::Bool(true))
-      ])
+      AttributeEntry::parse("d(-11, tr-3, 0.25, \"a b, c\")"),
+      Ok(AttributeEntry(
+        "d".to_string(),
+        vec![
+          ParseArgumentItem::Number(-11),
+          ParseArgumentItem::Str("tr-3".to_string()),
+          ParseArgumentItem::Float(0.25),
+          ParseArgumentItem::Str("a b, c".to_string())
+        ]
+      ))
     );
+  }
 
+  #[test]
+  fn test_parse_nested() {
     assert_eq!(
-      parse(vec![
-        LexItem::Character('a'),
-        LexItem::Paren,
-        LexItem::Minus,
-        LexItem::Number(1),
-        LexItem::Number(1),
-        LexItem::Comma,
-        LexItem::Character('t'),
-        LexItem::Character('r'),
-        LexItem::Minus,
-        LexItem::Character('3'),
-        LexItem::Paren
-      ]),
-      Ok(vec![
-        ParseItem::Key("a".to_string()),
-        ParseItem::Argument(ParseArgumentItem::Number(-11)),
-        ParseItem::Argument(ParseArgumentItem::Str("tr-3".to_string()))
-      ])
+      AttributeEntry::parse("d(a(1,b), c(), e(f(false)))"),
+      Ok(AttributeEntry(
+        "d".to_string(),
+        vec![
+          ParseArgumentItem::Call(
+            "a".to_string(),
+            vec![ParseArgumentItem::Number(1), ParseArgumentItem::Str("b".to_string())]
+          ),
+          ParseArgumentItem::Call("c".to_string(), vec![]),
+          ParseArgumentItem::Call(
+            "e".to_string(),
+            vec![ParseArgumentItem::Call(
+              "f".to_string(),
+              vec![ParseArgumentItem::Bool(false)]
+            )]
+          ),
+        ]
+      ))
     );
+  }
 
-    assert_eq!(
-      parse(vec![LexItem::Character('a')]),
-      Ok(vec![ParseItem::Key("a".to_string())])
-    );
+  #[test]
+  fn test_parse_errors() {
+    assert_eq!(AttributeEntry::parse("").unwrap_err().span, 0..0);
+    assert_eq!(AttributeEntry::parse("5(1)").unwrap_err().span, 0..1);
+    assert_eq!(AttributeEntry::parse("d(1, 2").unwrap_err().span, 1..2);
+    assert_eq!(AttributeEntry::parse("d(1 2)").unwrap_err().span, 4..5);
+    assert_eq!(AttributeEntry::parse("d(1,,2)").unwrap_err().span, 4..5);
+    assert_eq!(AttributeEntry::parse("d(1) e").unwrap_err().span, 5..6);
   }
 
   #[test]
-  fn test_parse_integration() {
+  fn test_parse_keywords() {
     assert_eq!(
-      AttributeEntry::from("d(1,2,test,true)".to_string()),
-      AttributeEntry(
-        "d".to_string(),
+      AttributeEntry::parse("moving(right, distance=5, period = 2.5)"),
+      Ok(AttributeEntry(
+        "moving".to_string(),
         vec![
-          ParseArgumentItem::Number(1),
-          ParseArgumentItem::Number(2),
-          ParseArgumentItem::Str("test".to_string()),
-          ParseArgumentItem::Bool(true)
+          ParseArgumentItem::Str("right".to_string()),
+          ParseArgumentItem::Keyword("distance".to_string(), Box::new(ParseArgumentItem::Number(5))),
+          ParseArgumentItem::Keyword("period".to_string(), Box::new(ParseArgumentItem::Float(2.5))),
         ]
-      )
-    );
-    assert_eq!(
-      AttributeEntry::from("d".to_string()),
-      AttributeEntry("d".to_string(), vec![])
-    );
-    assert_eq!(
-      AttributeEntry::from("d()".to_string()),
-      AttributeEntry("d".to_string(), vec![])
+      ))
     );
     assert_eq!(
-      AttributeEntry::from("d(a(1,b))".to_string()),
-      AttributeEntry(
-        "d".to_string(),
-        vec![
-          ParseArgumentItem::Str("a".to_string()),
-          ParseArgumentItem::Number(1),
-          ParseArgumentItem::Str("b".to_string()),
-        ]
-      )
+      AttributeEntry::parse("give(dash(capacity=2))"),
+      Ok(AttributeEntry(
+        "give".to_string(),
+        vec![ParseArgumentItem::Call(
+          "dash".to_string(),
+          vec![ParseArgumentItem::Keyword(
+            "capacity".to_string(),
+            Box::new(ParseArgumentItem::Number(2))
+          )]
+        )]
+      ))
     );
+    assert_eq!(AttributeEntry::parse("d(a=1, 2)").unwrap_err().span, 7..8);
+    assert_eq!(AttributeEntry::parse("d(a=)").unwrap_err().span, 4..5);
+    assert_eq!(AttributeEntry::parse("d(a=b=1)").unwrap_err().span, 5..6);
+  }
+
+  #[test]
+  fn test_annotate() {
+    let err = AttributeEntry::parse("d(1 2)").unwrap_err();
+    assert_eq!(err.annotate("d(1 2)"), "d(1 2)\n    ^");
   }
 }
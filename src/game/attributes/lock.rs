@@ -0,0 +1,108 @@
+//! Solid sprite opened by touching it while holding a matching `key`.
+//! `lock(color)`
+//!
+//! # Usage
+//! `color`: Name of the key that opens the lock
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use super::solid::{set_solid, solid_collider};
+use super::{Attribute, AttributeError, AttributeParams, Key, KeyState, ParamKind, ParamSpec};
+use crate::game::collision::{ContactSubscription, PlayerContacted};
+use crate::level::LevelId;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LockState {
+  Closed,
+  /// Opened by a key. Committed once a checkpoint is reached.
+  Open {
+    committed: bool,
+  },
+}
+
+#[derive(Component)]
+pub struct Lock {
+  pub color: String,
+  pub state: LockState,
+}
+
+impl Lock {
+  /// Keeps the lock open through later deaths.
+  pub fn commit(&mut self) {
+    if let LockState::Open { .. } = self.state {
+      self.state = LockState::Open { committed: true };
+    }
+  }
+
+  /// Closes the lock if it was opened since the last checkpoint. Returns true
+  /// if the lock closed.
+  pub fn revert(&mut self) -> bool {
+    if self.state == (LockState::Open { committed: false }) {
+      self.state = LockState::Closed;
+      true
+    } else {
+      false
+    }
+  }
+
+  /// Matches the collider and visibility of a lock to its state.
+  pub fn apply(&self, flags: &mut ColliderFlagsComponent, visibility: &mut Visibility) {
+    let closed = self.state == LockState::Closed;
+    set_solid(flags, closed);
+    visibility.is_visible = closed;
+  }
+}
+
+impl Attribute for Lock {
+  const KEY: &'static str = "lock";
+  const PARAMS: &'static [ParamSpec] = &[ParamSpec::required("color", ParamKind::Str)];
+
+  fn build(
+    commands: &mut Commands,
+    target: Entity,
+    _: LevelId,
+    position: Vec2,
+    params: AttributeParams,
+  ) -> Result<(), AttributeError> {
+    let mut collider = solid_collider(position);
+    collider.flags.active_events = ActiveEvents::CONTACT_EVENTS;
+
+    commands
+      .entity(target)
+      .insert(Lock {
+        color: params.str("color")?.to_string(),
+        state: LockState::Closed,
+      })
+      .insert(ContactSubscription)
+      .insert_bundle(collider);
+
+    Ok(())
+  }
+}
+
+/// Consumes [PlayerContacted] tags, opening locks if the player holds a key of
+/// the same color. The key is used up.
+pub fn on_lock_system(
+  mut commands: Commands,
+  mut lock: Query<(Entity, &mut Lock, &mut ColliderFlagsComponent, &mut Visibility), With<PlayerContacted>>,
+  mut keys: Query<(&mut Key, &mut Visibility), Without<Lock>>,
+) {
+  lock.for_each_mut(|(ent, mut lock, mut flags, mut visibility)| {
+    if lock.state == LockState::Closed {
+      let key = keys.iter_mut().find(|(key, _)| key.held() && key.color == lock.color);
+
+      if let Some((mut key, mut key_visibility)) = key {
+        if let KeyState::Held { committed } = key.state {
+          key.state = KeyState::Used { committed };
+        }
+        key_visibility.is_visible = false;
+
+        lock.state = LockState::Open { committed: false };
+        lock.apply(&mut flags, &mut visibility);
+      }
+    }
+
+    commands.entity(ent).remove::<PlayerContacted>();
+  });
+}
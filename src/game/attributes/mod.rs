@@ -6,71 +6,130 @@
 //! In most scenario's, they are simply tags to apply a system, but attributes
 //! additionally have the ability to take parameters to generalize their
 //! functionality.
+//!
+//! Parameters are declared by each attribute as a list of [ParamSpec]s, and can
+//! be supplied positionally (`moving(right, 5, 5)`), by name
+//! (`moving(dir=right, distance=5, dur=5)`), or a mix of both with the
+//! positional arguments first.
 
 use bevy::prelude::*;
 
-macro_rules! attribute_build_tree {
-  ($incoming_attr:expr, $commands:expr, $target:expr, $level:expr, $position:expr, [$($attribute:path),+]) => {
-    let entry = AttributeEntry::from($incoming_attr);
-    match entry.0.as_str() {
-      $(<$attribute>::KEY => <$attribute>::build($commands, $target, $level, $position, entry.1),)+
-      _ => panic!("Attempted to load invalid attribute with name {}", entry.0),
-    }
-  };
-}
-
 /// Attribute, as used with a [crate::sprite::SpriteType]
 pub trait Attribute {
   const KEY: &'static str;
-  fn build(commands: &mut Commands, target: Entity, level: LevelId, position: Vec2, params: Vec<ParseArgumentItem>);
-}
-
-/// Constructs attribute onto a given [Entity]. Used during level load (see
-/// [crate::level::load::load_level]).
-pub fn build_attribute(attribute: String, commands: &mut Commands, target: Entity, level: LevelId, position: Vec2) {
-  attribute_build_tree!(
-    attribute,
-    commands,
-    target,
-    level,
-    position,
-    [
-      Player,
-      Solid,
-      MovingSprite,
-      Deadly,
-      Checkpoint,
-      Transition,
-      Goal,
-      Dash,
-      GivableAttribute
-    ]
-  );
+  /// Parameters accepted by the attribute, in positional order.
+  const PARAMS: &'static [ParamSpec] = &[];
+  /// Whether the attribute can be given to and taken from the player with
+  /// `give` and `take`.
+  const ABILITY: bool = false;
+  fn build(
+    commands: &mut Commands,
+    target: Entity,
+    level: LevelId,
+    position: Vec2,
+    params: AttributeParams,
+  ) -> Result<(), AttributeError>;
+  /// Checks resolved parameters beyond their types, such as a file existing.
+  /// Run whenever the attribute is validated, and before it is built.
+  fn validate(_: &AttributeParams) -> Result<(), AttributeError> {
+    Ok(())
+  }
+  /// Removes the attribute from an [Entity]. Only required for abilities.
+  fn remove(_: &mut Commands, _: Entity) {}
 }
 
+mod ambience;
+mod bounce;
+mod camera_zone;
 mod checkpoint;
+mod collect;
+mod conveyor;
+mod crumble;
 mod dash;
 mod deadly;
+mod dialogue;
+mod door;
+mod enemy;
+mod falling;
+mod flip;
+mod flip_gravity;
+mod focus;
+mod force;
+mod friction;
 mod give;
 mod goal;
+mod gravity;
+mod group_control;
+mod key;
 mod lex;
+mod lock;
 mod moving;
+mod music;
+mod params;
+mod path;
+mod plate;
 mod player;
+mod pulse;
+mod refill;
+mod registry;
+mod semisolid;
+mod shooter;
+mod sign;
 mod solid;
+mod switch;
+mod take;
+mod timer_switch;
 mod transition;
+mod water;
+mod zone;
 
+pub use ambience::*;
+pub use bounce::*;
+pub use camera_zone::*;
 pub use checkpoint::*;
+pub use collect::*;
+pub use conveyor::*;
+pub use crumble::*;
 pub use dash::*;
 pub use deadly::*;
+pub use dialogue::*;
+pub use door::*;
+pub use enemy::*;
+pub use falling::*;
+pub use flip::*;
+pub use flip_gravity::*;
+pub use focus::*;
+pub use force::*;
+pub use friction::*;
 pub use give::*;
 pub use goal::*;
+pub use gravity::*;
+pub use group_control::*;
+pub use key::*;
+pub use lock::*;
 pub use moving::*;
+pub use music::*;
+pub use params::*;
+pub use path::*;
+pub use plate::*;
 pub use player::*;
+pub use pulse::*;
+pub use refill::*;
+pub use registry::*;
+pub use semisolid::*;
+pub use shooter::*;
+pub use sign::*;
 pub use solid::*;
+pub use switch::*;
+pub use take::*;
+pub use timer_switch::*;
 pub use transition::*;
+pub use water::*;
+pub use zone::*;
 
-use self::lex::{AttributeEntry, ParseArgumentItem};
+use super::ai::EnemySystemSteps;
 use super::physics::PlayerSimulationSteps;
+use super::signal::SignalSystemSteps;
 use crate::level::LevelId;
 
 /// [Plugin] for attributes
@@ -79,16 +138,71 @@ pub struct AttributePlugin;
 impl Plugin for AttributePlugin {
   fn build(&self, app: &mut App) {
     app
-      .add_system(moving_system.label(MovingAttributeSystemSteps::ApplyDeltaTranslation))
+      .init_resource::<MovementGroups>()
+      .init_resource::<MovementGroupCommands>()
+      .init_resource::<KeyCommands>()
+      .init_resource::<PlayedFoci>()
+      .add_system(
+        handle_movement_group_commands
+          .label(MovingAttributeSystemSteps::ApplyGroupCommands)
+          .before(MovingAttributeSystemSteps::TickGroupClocks),
+      )
+      .add_system(movement_group_clock_system.label(MovingAttributeSystemSteps::TickGroupClocks))
+      .add_system(
+        moving_system
+          .label(MovingAttributeSystemSteps::ApplyDeltaTranslation)
+          .after(MovingAttributeSystemSteps::TickGroupClocks),
+      )
+      .add_system(
+        path_system
+          .label(MovingAttributeSystemSteps::ApplyDeltaTranslation)
+          .after(MovingAttributeSystemSteps::TickGroupClocks),
+      )
+      .add_system(falling_system.label(MovingAttributeSystemSteps::ApplyDeltaTranslation))
+      .add_system(zone_system.label(PlayerSimulationSteps::ApplyZones))
+      .add_system(water_system.after(PlayerSimulationSteps::ApplyZones))
+      .add_system(semisolid_system)
+      .add_system(crumble_system)
+      .add_system(pulse_system)
+      .add_system(shooter_system)
+      .add_system(projectile_system)
+      .add_system(on_bounce_system)
+      .add_system(on_flip_gravity_system)
+      .add_system(on_switch_system.before(SignalSystemSteps::ApplyCommands))
+      .add_system(plate_system.before(SignalSystemSteps::ApplyCommands))
+      .add_system(timer_switch_system.before(SignalSystemSteps::ApplyCommands))
+      .add_system(switch_sprite_system.after(SignalSystemSteps::ApplyCommands))
+      .add_system(door_system.after(SignalSystemSteps::ApplyCommands))
+      .add_system(
+        group_control_system
+          .after(SignalSystemSteps::ApplyCommands)
+          .before(MovingAttributeSystemSteps::ApplyGroupCommands),
+      )
       .add_system(
         move_player
           .after(MovingAttributeSystemSteps::ApplyDeltaTranslation)
           .after(PlayerSimulationSteps::ApplyMoving),
       )
-      .add_system(on_death_system)
+      .add_system(on_enemy_system.label(EnemySystemSteps::ResolveContacts))
+      .add_system(on_death_system.after(EnemySystemSteps::ResolveContacts))
       .add_system(on_checkpoint_system)
       .add_system(on_transition_system)
       .add_system(on_goal_system)
-      .add_system(on_give_system);
+      .add_system(dialogue_system)
+      .add_system(on_focus_system)
+      .add_system(on_music_system)
+      .add_system(on_ambience_system)
+      .add_system(focus_target_system)
+      .add_system(collect_save_system)
+      .add_system(on_collect_system)
+      .add_system(on_key_system)
+      .add_system(on_lock_system)
+      .add_system(key_follow_system)
+      .add_system(handle_key_commands)
+      .add_system(on_give_system)
+      .add_system(on_take_system)
+      .add_system(on_dash_removed_system)
+      .add_system(on_refill_system)
+      .add_system(refill_respawn_system);
   }
 }
@@ -1,4 +1,5 @@
-//! Defines a cyclically moving sprite. `moving(dir, distance, time)`
+//! Defines a cyclically moving sprite. `moving(dir, distance, dur, phase,
+//! group)`
 //!
 //!
 //! # Usage
@@ -7,15 +8,26 @@
 //! `distance`: Distance away from origin in direction of `dir`
 //!
 //! `dur`: Duration of the sprite's cycle in seconds
+//!
+//! `phase`: Fraction of the cycle the sprite starts at, e.g. `0.5` starts at
+//! `distance` (default 0)
+//!
+//! `group`: Optional [MovementGroup] id. Sprites in the same group share one
+//! clock, which can be paused or reversed with `group_control` (see
+//! [GroupControl](super::GroupControl)).
+//!
+//! Parameters may also be given by name, e.g.
+//! `moving(dir=right, distance=5, dur=5, group=1)`
 
+use std::collections::{HashMap, VecDeque};
 use std::f32::consts::PI;
 
 use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
 
-use super::lex::ParseArgumentItem;
-use super::{Attribute, Player};
+use super::{Attribute, AttributeError, AttributeParams, ParamKind, ParamSpec, PathSprite, Player};
 use crate::game::physics::PhysicsCommands;
+use crate::level::load::LevelLoadComplete;
 use crate::level::LevelId;
 
 /// Direction of sprite movement.
@@ -28,17 +40,13 @@ pub enum MovingDirection {
 }
 
 impl MovingDirection {
-  pub fn from_param(value: ParseArgumentItem) -> Option<Self> {
-    if let ParseArgumentItem::Str(str) = value {
-      match str.as_str() {
-        "right" => Some(Self::Right),
-        "down" => Some(Self::Down),
-        "left" => Some(Self::Left),
-        "up" => Some(Self::Up),
-        _ => None,
-      }
-    } else {
-      panic!("Failed to parse param {:?}", value)
+  pub fn from_param(value: &str) -> Option<Self> {
+    match value {
+      "right" => Some(Self::Right),
+      "down" => Some(Self::Down),
+      "left" => Some(Self::Left),
+      "up" => Some(Self::Up),
+      _ => None,
     }
   }
 }
@@ -54,6 +62,95 @@ impl Into<Vec2> for MovingDirection {
   }
 }
 
+/// Translation a moving sprite made during the last step, carried over to a
+/// [Player] riding on top of it (see [move_player]).
+#[derive(Component, Default)]
+pub struct PlatformDelta(pub Vec2);
+
+/// Tags a moving sprite as following the shared clock of a group, rather than
+/// its own.
+#[derive(Component, Clone, Copy)]
+pub struct MovementGroup(pub u32);
+
+impl MovementGroup {
+  /// Reads the optional `group` parameter of a moving attribute.
+  pub fn from_params(params: &AttributeParams) -> Result<Option<Self>, AttributeError> {
+    if params.is_set("group") {
+      Ok(Some(Self(params.unsigned("group")?)))
+    } else {
+      Ok(None)
+    }
+  }
+}
+
+/// Clock shared by all members of a [MovementGroup].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct GroupClock {
+  pub time: f32,
+  pub paused: bool,
+  pub reversed: bool,
+}
+
+impl GroupClock {
+  pub fn tick(&mut self, delta_t: f32) {
+    if !self.paused {
+      self.time += if self.reversed { -delta_t } else { delta_t };
+    }
+  }
+}
+
+/// [Res] of the clock for each [MovementGroup] in the level.
+#[derive(Default)]
+pub struct MovementGroups(HashMap<u32, GroupClock>);
+
+impl MovementGroups {
+  /// Current time of a group's clock.
+  pub fn time(&self, group: u32) -> f32 {
+    self.clock(group).time
+  }
+
+  /// State of a group's clock.
+  pub fn clock(&self, group: u32) -> GroupClock {
+    self.0.get(&group).copied().unwrap_or_default()
+  }
+}
+
+pub enum MovementGroupCommand {
+  Pause(u32),
+  Resume(u32),
+  Reverse(u32),
+  /// Returns every moving sprite and group clock to the start of its cycle
+  Reset,
+}
+
+/// A command queue to control [MovementGroup] clocks.
+#[derive(Default)]
+pub struct MovementGroupCommands {
+  queue: VecDeque<MovementGroupCommand>,
+}
+
+impl MovementGroupCommands {
+  pub fn pause(&mut self, group: u32) {
+    self.queue.push_back(MovementGroupCommand::Pause(group));
+  }
+
+  pub fn resume(&mut self, group: u32) {
+    self.queue.push_back(MovementGroupCommand::Resume(group));
+  }
+
+  pub fn reverse(&mut self, group: u32) {
+    self.queue.push_back(MovementGroupCommand::Reverse(group));
+  }
+
+  pub fn reset(&mut self) {
+    self.queue.push_back(MovementGroupCommand::Reset);
+  }
+
+  pub fn pop(&mut self) -> Option<MovementGroupCommand> {
+    self.queue.pop_front()
+  }
+}
+
 /// `moving` attribute state.
 #[derive(Component)]
 pub struct MovingSprite {
@@ -65,31 +162,47 @@ pub struct MovingSprite {
   starting_position: Vec2,
   movement_vect: Vec2,
   current_time: f32,
+  time_offset: f32,
   player_delta: f32,
 }
 
 impl MovingSprite {
-  pub fn new(dir: MovingDirection, distance: i32, duration: i32, position: Vec2) -> Self {
+  pub fn new(dir: MovingDirection, distance: i32, duration: i32, phase: f32, position: Vec2) -> Self {
     let sprite_distance: f32 = distance as f32;
     let vec_dir: Vec2 = dir.into();
-    MovingSprite {
+    let mut moving = MovingSprite {
       dir,
       duration: duration as f32,
       distance: sprite_distance,
       starting_position: position,
       movement_vect: (vec_dir * sprite_distance),
+      time_offset: phase * duration as f32,
       ..MovingSprite::default()
-    }
+    };
+    moving.reset();
+    moving
   }
 
   /// Increments time and recalculates [Self::delta]
   pub fn increment_time(&mut self, delta_t: f32) {
-    self.current_time += delta_t;
-    let delta = 0.5 * (((2.0 * PI) / self.duration) * self.current_time + PI).cos() + 0.5;
+    self.set_time(self.current_time + delta_t);
+  }
+
+  /// Sets time, such as from a [GroupClock], and recalculates [Self::delta]
+  pub fn set_time(&mut self, time: f32) {
+    self.current_time = time;
+    let cycle_time = self.current_time + self.time_offset;
+    let delta = 0.5 * (((2.0 * PI) / self.duration) * cycle_time + PI).cos() + 0.5;
     self.player_delta = delta - self.delta;
     self.delta = delta;
   }
 
+  /// Returns to the start of the cycle without carrying a riding player.
+  pub fn reset(&mut self) {
+    self.set_time(0.0);
+    self.player_delta = 0.0;
+  }
+
   pub fn player_delta(&self) -> Vec2 {
     self.player_delta * self.movement_vect
   }
@@ -113,6 +226,7 @@ impl Default for MovingSprite {
       starting_position: Vec2::ZERO,
       movement_vect: Vec2::ZERO,
       current_time: 0.0,
+      time_offset: 0.0,
       delta: 0.0,
       player_delta: 0.0,
     }
@@ -121,40 +235,43 @@ impl Default for MovingSprite {
 
 impl Attribute for MovingSprite {
   const KEY: &'static str = "moving";
+  const PARAMS: &'static [ParamSpec] = &[
+    ParamSpec::required("dir", ParamKind::Str),
+    ParamSpec::required("distance", ParamKind::Number),
+    ParamSpec::required("dur", ParamKind::Number),
+    ParamSpec::float("phase", 0.0),
+    ParamSpec::optional("group", ParamKind::Number),
+  ];
 
-  fn build(commands: &mut Commands, target: Entity, _: LevelId, position: Vec2, params: Vec<ParseArgumentItem>) {
-    let direction_num = params
-      .get(0)
-      .expect("Moving Sprite Attribute was not supplied parameter 0");
-    let direction = MovingDirection::from_param(direction_num.clone()).unwrap_or_else(|| {
-      panic!(
-        "Was supplied invalid moving direction of {:?} for moving attribute",
-        direction_num
-      )
-    });
-
-    let distance_item = &*params
-      .get(1)
-      .expect("Moving Sprite Attribute was not supplied parameter 1");
-    let time_item = &*params
-      .get(2)
-      .expect("Moving Sprite Attribute was not supplied parameter 2");
-
-    let distance = if let ParseArgumentItem::Number(n) = distance_item {
-      *n as i32
-    } else {
-      panic!("Distance param was not a number!")
-    };
-    let time = if let ParseArgumentItem::Number(n) = time_item {
-      *n as i32
-    } else {
-      panic!("Distance param was not a number!")
-    };
+  fn build(
+    commands: &mut Commands,
+    target: Entity,
+    _: LevelId,
+    position: Vec2,
+    params: AttributeParams,
+  ) -> Result<(), AttributeError> {
+    let direction = MovingDirection::from_param(params.str("dir")?)
+      .ok_or_else(|| AttributeError::invalid("dir", "must be one of left, right, up or down"))?;
+    let distance = params.number("distance")? as i32;
+    let time = params.number("dur")? as i32;
+    let phase = params.float("phase")?;
+    let group = MovementGroup::from_params(&params)?;
+
+    if time <= 0 {
+      return Err(AttributeError::invalid("dur", "must be greater than zero"));
+    }
 
     commands
       .entity(target)
       .insert(ColliderPositionSync::Discrete)
-      .insert(MovingSprite::new(direction, distance, time, position));
+      .insert(PlatformDelta::default())
+      .insert(MovingSprite::new(direction, distance, time, phase, position));
+
+    if let Some(group) = group {
+      commands.entity(target).insert(group);
+    }
+
+    Ok(())
   }
 }
 
@@ -163,33 +280,130 @@ impl Attribute for MovingSprite {
 /// (see [super::AttributePlugin])
 #[derive(Debug, Hash, PartialEq, Eq, Clone, SystemLabel)]
 pub enum MovingAttributeSystemSteps {
+  ApplyGroupCommands,
+  TickGroupClocks,
   ApplyDeltaTranslation,
 }
 
+/// Consumes [MovementGroupCommands], and resets all movement when a level
+/// finishes loading so that every load starts identically.
+pub fn handle_movement_group_commands(
+  mut group_commands: ResMut<MovementGroupCommands>,
+  mut groups: ResMut<MovementGroups>,
+  level_loaded: Query<(), Added<LevelLoadComplete>>,
+  mut moving_sprite: Query<&mut MovingSprite>,
+  mut path_sprite: Query<&mut PathSprite>,
+) {
+  if level_loaded.iter().next().is_some() {
+    group_commands.reset();
+  }
+
+  while let Some(command) = group_commands.pop() {
+    match command {
+      MovementGroupCommand::Pause(group) => groups.0.entry(group).or_default().paused = true,
+      MovementGroupCommand::Resume(group) => groups.0.entry(group).or_default().paused = false,
+      MovementGroupCommand::Reverse(group) => {
+        let clock = groups.0.entry(group).or_default();
+        clock.reversed = !clock.reversed;
+      },
+      MovementGroupCommand::Reset => {
+        groups.0.values_mut().for_each(|clock| *clock = GroupClock::default());
+        moving_sprite.for_each_mut(|mut moving| moving.reset());
+        path_sprite.for_each_mut(|mut path| path.reset());
+      },
+    }
+  }
+}
+
+/// System to advance the clock of every [MovementGroup] per change in [Time].
+pub fn movement_group_clock_system(
+  time: Res<Time>,
+  physics_commands: Res<PhysicsCommands>,
+  mut groups: ResMut<MovementGroups>,
+  new_members: Query<&MovementGroup, Added<MovementGroup>>,
+) {
+  new_members.for_each(|group| {
+    groups.0.entry(group.0).or_default();
+  });
+
+  if physics_commands.paused() {
+    return;
+  }
+
+  let delta_t = time.delta().as_secs_f32();
+  groups.0.values_mut().for_each(|clock| clock.tick(delta_t));
+}
+
 /// System to move all moving sprites per change in [Time].
 pub fn moving_system(
   time: Res<Time>,
   physics_commands: Res<PhysicsCommands>,
-  mut moving_sprite: Query<(&mut MovingSprite, &mut ColliderPositionComponent)>,
+  groups: Res<MovementGroups>,
+  mut moving_sprite: Query<(
+    &mut MovingSprite,
+    Option<&MovementGroup>,
+    &mut PlatformDelta,
+    &mut ColliderPositionComponent,
+  )>,
 ) {
   if physics_commands.paused() {
     return;
   }
 
-  moving_sprite.for_each_mut(|(mut moving, mut collider_position)| {
-    moving.increment_time(time.delta().as_secs_f32());
+  moving_sprite.for_each_mut(|(mut moving, group, mut platform_delta, mut collider_position)| {
+    match group {
+      Some(group) => moving.set_time(groups.time(group.0)),
+      None => moving.increment_time(time.delta().as_secs_f32()),
+    }
+    platform_delta.0 = moving.player_delta();
     collider_position.0 = moving.get_position().into();
   });
 }
 
 /// Moves the [Player] if they are on top of a moving sprite.
-pub fn move_player(mut player: Query<(&mut RigidBodyPositionComponent, &Player)>, moving_sprite: Query<&MovingSprite>) {
+pub fn move_player(mut player: Query<(&mut RigidBodyPositionComponent, &Player)>, platforms: Query<&PlatformDelta>) {
   if let Ok((mut player_pos, player_c)) = player.get_single_mut() {
     if let Some(entity) = player_c.on_moving_entity {
-      if let Ok(moving) = moving_sprite.get_component::<MovingSprite>(entity) {
-        let delta = moving.player_delta();
+      if let Ok(PlatformDelta(delta)) = platforms.get(entity) {
         player_pos.0.position.append_translation_mut(&[delta.x, delta.y].into());
       }
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use bevy::prelude::*;
+
+  use crate::game::attributes::*;
+
+  #[test]
+  fn test_moving_phase() {
+    let mut moving = MovingSprite::new(MovingDirection::Right, 4, 2, 0.0, Vec2::ZERO);
+    assert_eq!(moving.get_position(), Vec2::ZERO);
+    moving.increment_time(1.0);
+    assert!(moving.get_position().abs_diff_eq(Vec2::new(4.0, 0.0), 1e-4));
+
+    let mut moving = MovingSprite::new(MovingDirection::Right, 4, 2, 0.5, Vec2::ZERO);
+    assert!(moving.get_position().abs_diff_eq(Vec2::new(4.0, 0.0), 1e-4));
+    assert_eq!(moving.player_delta(), Vec2::ZERO);
+    moving.set_time(1.0);
+    assert!(moving.get_position().abs_diff_eq(Vec2::ZERO, 1e-4));
+  }
+
+  #[test]
+  fn test_group_clock() {
+    let mut clock = GroupClock::default();
+    clock.tick(1.0);
+    assert_eq!(clock.time, 1.0);
+
+    clock.paused = true;
+    clock.tick(1.0);
+    assert_eq!(clock.time, 1.0);
+
+    clock.paused = false;
+    clock.reversed = true;
+    clock.tick(0.5);
+    assert_eq!(clock.time, 0.5);
+  }
+}
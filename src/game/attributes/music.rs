@@ -0,0 +1,66 @@
+//! Changes the level's music when the player touches the sprite.
+//! `music(file)`
+//!
+//! # Usage
+//! `file`: Quoted name of a track in the music directory, e.g.
+//! `music("cave.ogg")`
+//!
+//! The playing track crossfades into the new one (see [crate::game::music]). A
+//! line of these sprites can mark the edge of a region with its own music.
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use super::give::pickup_collider;
+use super::{Attribute, AttributeError, AttributeParams, ParamKind, ParamSpec};
+use crate::game::collision::{ContactQuery, ContactSubscription, PlayerContacted};
+use crate::game::music::{track_exists, MusicCommands};
+use crate::level::LevelId;
+
+#[derive(Component)]
+pub struct MusicTrigger(pub String);
+
+impl Attribute for MusicTrigger {
+  const KEY: &'static str = "music";
+  const PARAMS: &'static [ParamSpec] = &[ParamSpec::required("file", ParamKind::Str)];
+
+  fn build(
+    commands: &mut Commands,
+    target: Entity,
+    _: LevelId,
+    position: Vec2,
+    params: AttributeParams,
+  ) -> Result<(), AttributeError> {
+    commands
+      .entity(target)
+      .insert(MusicTrigger(params.str("file")?.to_string()))
+      .insert(ContactSubscription)
+      .insert_bundle(pickup_collider(position))
+      .insert(ColliderPositionSync::Discrete);
+
+    Ok(())
+  }
+
+  fn validate(params: &AttributeParams) -> Result<(), AttributeError> {
+    let file = params.str("file")?;
+    if file.is_empty() {
+      Err(AttributeError::invalid("file", "must not be empty"))
+    } else if !track_exists(file) {
+      Err(AttributeError::invalid("file", "is not a track in the music directory"))
+    } else {
+      Ok(())
+    }
+  }
+}
+
+/// Consumes [PlayerContacted] tags, changing the music.
+pub fn on_music_system(
+  mut commands: Commands,
+  triggers: ContactQuery<MusicTrigger>,
+  mut music_commands: ResMut<MusicCommands>,
+) {
+  triggers.for_each(|(ent, trigger)| {
+    music_commands.play_music(trigger.0.clone());
+    commands.entity(ent).remove::<PlayerContacted>();
+  });
+}
@@ -0,0 +1,573 @@
+//! Typed parameter schemas for attributes.
+//!
+//! Every [Attribute](super::Attribute) declares its parameters as a list of
+//! [ParamSpec]s. Incoming arguments are resolved against that schema before the
+//! attribute is built, so a malformed manifest entry is reported as an
+//! [AttributeError] instead of crashing the game mid-load.
+
+use std::fmt;
+
+use super::lex::{ParseArgumentItem, ParseError};
+use crate::level::LevelId;
+
+/// Type of value a parameter accepts.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ParamKind {
+  Number,
+  /// Accepts both integers and floats.
+  Float,
+  Str,
+  Bool,
+  /// Another attribute that can be given to the player, such as `dash` or
+  /// `dash(2)`. See [Attribute::ABILITY](super::Attribute::ABILITY).
+  Ability,
+  /// Nested call with its own parameters, such as `p(3, 0)`.
+  Call(&'static str, &'static [ParamSpec]),
+  /// Any number of values of another kind. Takes every positional argument
+  /// from its place in the schema onwards, so parameters after it must be
+  /// given by name.
+  List(&'static ParamKind),
+}
+
+impl ParamKind {
+  /// Returns true if the argument is of this kind.
+  pub fn matches(&self, item: &ParseArgumentItem) -> bool {
+    match (self, item) {
+      (ParamKind::Call(key, _), ParseArgumentItem::Call(name, _)) => key == name,
+      (ParamKind::List(kind), ParseArgumentItem::List(items)) => items.iter().all(|item| kind.matches(item)),
+      _ => matches!(
+        (self, item),
+        (ParamKind::Number, ParseArgumentItem::Number(_))
+          | (
+            ParamKind::Float,
+            ParseArgumentItem::Number(_) | ParseArgumentItem::Float(_)
+          )
+          | (ParamKind::Str, ParseArgumentItem::Str(_))
+          | (ParamKind::Bool, ParseArgumentItem::Bool(_))
+          | (
+            ParamKind::Ability,
+            ParseArgumentItem::Str(_) | ParseArgumentItem::Call(_, _)
+          )
+      ),
+    }
+  }
+
+  /// Checks an argument for parameter `param` against this kind, including
+  /// the arguments of nested calls.
+  fn check(&self, param: &'static str, item: &ParseArgumentItem) -> Result<(), AttributeError> {
+    match (self, item) {
+      (ParamKind::List(kind), ParseArgumentItem::List(items)) => {
+        items.iter().try_for_each(|item| kind.check(param, item))
+      },
+      (ParamKind::Call(key, schema), ParseArgumentItem::Call(name, args)) if key == name => {
+        AttributeParams::resolve(schema, args.clone())
+          .map(|_| ())
+          .map_err(|err| AttributeError::invalid(param, format!("has an invalid `{}`, {}", key, err)))
+      },
+      _ if self.matches(item) => Ok(()),
+      _ => Err(AttributeError::wrong_type(param, *self, item)),
+    }
+  }
+}
+
+impl fmt::Display for ParamKind {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      ParamKind::Number => write!(f, "whole number"),
+      ParamKind::Float => write!(f, "number"),
+      ParamKind::Str => write!(f, "string"),
+      ParamKind::Bool => write!(f, "bool"),
+      ParamKind::Ability => write!(f, "player ability"),
+      ParamKind::Call(key, schema) => {
+        let params: Vec<_> = schema
+          .iter()
+          .map(|spec| {
+            if spec.default == ParamDefault::Required {
+              spec.name.to_string()
+            } else {
+              format!("{}?", spec.name)
+            }
+          })
+          .collect();
+        write!(f, "`{}({})`", key, params.join(", "))
+      },
+      ParamKind::List(kind) => write!(f, "list of {}", kind),
+    }
+  }
+}
+
+/// How a parameter behaves when it is not supplied.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ParamDefault {
+  /// Parameter must be supplied.
+  Required,
+  /// Parameter may be omitted, and has no value if it is.
+  Optional,
+  /// Parameter may be omitted, and takes this value if it is.
+  Number(i64),
+  Float(f32),
+  Str(&'static str),
+  Bool(bool),
+}
+
+impl ParamDefault {
+  fn value(&self) -> Option<ParseArgumentItem> {
+    match self {
+      ParamDefault::Required | ParamDefault::Optional => None,
+      ParamDefault::Number(n) => Some(ParseArgumentItem::Number(*n)),
+      ParamDefault::Float(n) => Some(ParseArgumentItem::Float(*n)),
+      ParamDefault::Str(s) => Some(ParseArgumentItem::Str(s.to_string())),
+      ParamDefault::Bool(b) => Some(ParseArgumentItem::Bool(*b)),
+    }
+  }
+}
+
+/// Describes a single parameter of an attribute.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ParamSpec {
+  pub name: &'static str,
+  pub kind: ParamKind,
+  pub default: ParamDefault,
+}
+
+impl ParamSpec {
+  pub const fn required(name: &'static str, kind: ParamKind) -> Self {
+    Self {
+      name,
+      kind,
+      default: ParamDefault::Required,
+    }
+  }
+
+  pub const fn optional(name: &'static str, kind: ParamKind) -> Self {
+    Self {
+      name,
+      kind,
+      default: ParamDefault::Optional,
+    }
+  }
+
+  pub const fn number(name: &'static str, default: i64) -> Self {
+    Self {
+      name,
+      kind: ParamKind::Number,
+      default: ParamDefault::Number(default),
+    }
+  }
+
+  pub const fn float(name: &'static str, default: f32) -> Self {
+    Self {
+      name,
+      kind: ParamKind::Float,
+      default: ParamDefault::Float(default),
+    }
+  }
+
+  pub const fn string(name: &'static str, default: &'static str) -> Self {
+    Self {
+      name,
+      kind: ParamKind::Str,
+      default: ParamDefault::Str(default),
+    }
+  }
+
+  pub const fn boolean(name: &'static str, default: bool) -> Self {
+    Self {
+      name,
+      kind: ParamKind::Bool,
+      default: ParamDefault::Bool(default),
+    }
+  }
+
+  /// Required list of values, taking every remaining positional argument.
+  pub const fn list(name: &'static str, kind: &'static ParamKind) -> Self {
+    Self {
+      name,
+      kind: ParamKind::List(kind),
+      default: ParamDefault::Required,
+    }
+  }
+}
+
+/// Arguments of an attribute, resolved against its schema.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AttributeParams(Vec<(&'static str, Option<ParseArgumentItem>)>);
+
+impl AttributeParams {
+  /// Matches positional and keyword arguments to the schema, filling in
+  /// defaults and checking types.
+  pub fn resolve(schema: &[ParamSpec], args: Vec<ParseArgumentItem>) -> Result<Self, AttributeError> {
+    let mut values: Vec<Option<ParseArgumentItem>> = vec![None; schema.len()];
+    let mut positional = 0;
+    let list = schema.iter().position(|spec| matches!(spec.kind, ParamKind::List(_)));
+
+    for arg in args {
+      if let ParseArgumentItem::Keyword(name, value) = arg {
+        let index = schema
+          .iter()
+          .position(|spec| spec.name == name)
+          .ok_or(AttributeError::UnknownParameter(name))?;
+        if values[index].is_some() {
+          return Err(AttributeError::DuplicateArgument(schema[index].name));
+        }
+        values[index] = match (schema[index].kind, *value) {
+          // A list given by name holds a single value
+          (ParamKind::List(_), value) => Some(ParseArgumentItem::List(vec![value])),
+          (_, value) => Some(value),
+        };
+      } else {
+        positional += 1;
+        match list {
+          Some(index) if positional > index => match &mut values[index] {
+            Some(ParseArgumentItem::List(items)) => items.push(arg),
+            slot => *slot = Some(ParseArgumentItem::List(vec![arg])),
+          },
+          _ => {
+            if let Some(slot) = values.get_mut(positional - 1) {
+              *slot = Some(arg);
+            }
+          },
+        }
+      }
+    }
+
+    if list.is_none() && positional > schema.len() {
+      return Err(AttributeError::TooManyArguments {
+        expected: schema.len(),
+        found: positional,
+      });
+    }
+
+    schema
+      .iter()
+      .zip(values.into_iter())
+      .map(|(spec, value)| match value {
+        Some(arg) => spec.kind.check(spec.name, &arg).map(|_| (spec.name, Some(arg))),
+        None => {
+          if spec.default == ParamDefault::Required {
+            Err(AttributeError::MissingArgument(spec.name))
+          } else {
+            Ok((spec.name, spec.default.value()))
+          }
+        },
+      })
+      .collect::<Result<Vec<_>, _>>()
+      .map(Self)
+  }
+
+  /// Returns the value of a parameter, if it has one.
+  pub fn get(&self, name: &'static str) -> Option<&ParseArgumentItem> {
+    self
+      .0
+      .iter()
+      .find(|(param, _)| *param == name)
+      .and_then(|(_, value)| value.as_ref())
+  }
+
+  pub fn is_set(&self, name: &'static str) -> bool {
+    self.get(name).is_some()
+  }
+
+  pub fn number(&self, name: &'static str) -> Result<i64, AttributeError> {
+    match self.get(name) {
+      Some(ParseArgumentItem::Number(n)) => Ok(*n),
+      Some(other) => Err(AttributeError::wrong_type(name, ParamKind::Number, other)),
+      None => Err(AttributeError::MissingArgument(name)),
+    }
+  }
+
+  /// Returns a number parameter that must not be negative.
+  pub fn unsigned(&self, name: &'static str) -> Result<u32, AttributeError> {
+    u32::try_from(self.number(name)?).map_err(|_| AttributeError::invalid(name, "must be a positive number"))
+  }
+
+  pub fn float(&self, name: &'static str) -> Result<f32, AttributeError> {
+    match self.get(name) {
+      Some(ParseArgumentItem::Number(n)) => Ok(*n as f32),
+      Some(ParseArgumentItem::Float(n)) => Ok(*n),
+      Some(other) => Err(AttributeError::wrong_type(name, ParamKind::Float, other)),
+      None => Err(AttributeError::MissingArgument(name)),
+    }
+  }
+
+  pub fn str(&self, name: &'static str) -> Result<&str, AttributeError> {
+    match self.get(name) {
+      Some(ParseArgumentItem::Str(s)) => Ok(s.as_str()),
+      Some(other) => Err(AttributeError::wrong_type(name, ParamKind::Str, other)),
+      None => Err(AttributeError::MissingArgument(name)),
+    }
+  }
+
+  pub fn bool(&self, name: &'static str) -> Result<bool, AttributeError> {
+    match self.get(name) {
+      Some(ParseArgumentItem::Bool(b)) => Ok(*b),
+      Some(other) => Err(AttributeError::wrong_type(name, ParamKind::Bool, other)),
+      None => Err(AttributeError::MissingArgument(name)),
+    }
+  }
+
+  /// Returns the values of a list parameter.
+  pub fn list(&self, name: &'static str) -> Result<&[ParseArgumentItem], AttributeError> {
+    match self.get(name) {
+      Some(ParseArgumentItem::List(items)) => Ok(items.as_slice()),
+      Some(other) => Err(AttributeError::invalid(
+        name,
+        format!("should be a list, found {:?}", other),
+      )),
+      None => Err(AttributeError::MissingArgument(name)),
+    }
+  }
+
+  /// Resolves each value of a list of nested calls against the call's schema.
+  pub fn calls(&self, name: &'static str, schema: &[ParamSpec]) -> Result<Vec<Self>, AttributeError> {
+    self
+      .list(name)?
+      .iter()
+      .map(|item| match item {
+        ParseArgumentItem::Call(_, args) => Self::resolve(schema, args.clone()),
+        other => Err(AttributeError::invalid(
+          name,
+          format!("should only hold calls, found {:?}", other),
+        )),
+      })
+      .collect()
+  }
+
+  /// Returns the key and arguments of an ability parameter.
+  pub fn ability(&self, name: &'static str) -> Result<(&str, &[ParseArgumentItem]), AttributeError> {
+    match self.get(name) {
+      Some(ParseArgumentItem::Str(key)) => Ok((key.as_str(), &[])),
+      Some(ParseArgumentItem::Call(key, args)) => Ok((key.as_str(), args.as_slice())),
+      Some(other) => Err(AttributeError::wrong_type(name, ParamKind::Ability, other)),
+      None => Err(AttributeError::MissingArgument(name)),
+    }
+  }
+}
+
+/// Problem found while parsing or building an attribute.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AttributeError {
+  /// The attribute string could not be parsed.
+  Syntax(ParseError),
+  /// No attribute exists with the given key.
+  UnknownAttribute(String),
+  /// The attribute exists, but cannot be given to or taken from the player.
+  NotAnAbility(String),
+  /// A required parameter was not supplied.
+  MissingArgument(&'static str),
+  /// A keyword argument does not match any parameter.
+  UnknownParameter(String),
+  /// A parameter was given a value more than once.
+  DuplicateArgument(&'static str),
+  /// More arguments were supplied than the attribute accepts.
+  TooManyArguments { expected: usize, found: usize },
+  /// An argument was of the wrong type.
+  WrongType {
+    param: &'static str,
+    expected: ParamKind,
+    found: ParseArgumentItem,
+  },
+  /// An argument was of the right type, but its value is not allowed.
+  InvalidArgument { param: &'static str, reason: String },
+}
+
+impl AttributeError {
+  pub fn invalid<S: ToString>(param: &'static str, reason: S) -> Self {
+    Self::InvalidArgument {
+      param,
+      reason: reason.to_string(),
+    }
+  }
+
+  fn wrong_type(param: &'static str, expected: ParamKind, found: &ParseArgumentItem) -> Self {
+    Self::WrongType {
+      param,
+      expected,
+      found: found.clone(),
+    }
+  }
+}
+
+impl fmt::Display for AttributeError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      AttributeError::Syntax(err) => write!(f, "syntax error, {}", err),
+      AttributeError::UnknownAttribute(key) => write!(f, "unknown attribute `{}`", key),
+      AttributeError::NotAnAbility(key) => write!(f, "`{}` cannot be given to or taken from the player", key),
+      AttributeError::MissingArgument(param) => write!(f, "missing argument `{}`", param),
+      AttributeError::UnknownParameter(param) => write!(f, "unknown parameter `{}`", param),
+      AttributeError::DuplicateArgument(param) => write!(f, "argument `{}` given more than once", param),
+      AttributeError::TooManyArguments { expected, found } => {
+        write!(f, "expected at most {} arguments, found {}", expected, found)
+      },
+      AttributeError::WrongType { param, expected, found } => {
+        write!(f, "argument `{}` should be a {}, found {:?}", param, expected, found)
+      },
+      AttributeError::InvalidArgument { param, reason } => write!(f, "argument `{}` {}", param, reason),
+    }
+  }
+}
+
+/// [AttributeError] located within a level, as reported during level load.
+#[derive(Clone, Debug)]
+pub struct AttributeBuildError {
+  pub level: LevelId,
+  pub sprite: String,
+  pub attribute: String,
+  pub error: AttributeError,
+}
+
+impl fmt::Display for AttributeBuildError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "Level {}, sprite `{}`, attribute `{}`: {}",
+      self.level, self.sprite, self.attribute, self.error
+    )?;
+
+    if let AttributeError::Syntax(err) = &self.error {
+      write!(f, "\n{}", err.annotate(&self.attribute))?;
+    }
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::game::attributes::lex::ParseArgumentItem;
+  use crate::game::attributes::params::*;
+
+  const SCHEMA: &[ParamSpec] = &[
+    ParamSpec::required("id", ParamKind::Number),
+    ParamSpec::number("x", 0),
+    ParamSpec::optional("name", ParamKind::Str),
+    ParamSpec::float("speed", 1.5),
+  ];
+
+  #[test]
+  fn test_resolve_defaults() {
+    let params = AttributeParams::resolve(SCHEMA, vec![ParseArgumentItem::Number(3)]).unwrap();
+    assert_eq!(params.number("id"), Ok(3));
+    assert_eq!(params.number("x"), Ok(0));
+    assert!(!params.is_set("name"));
+    assert_eq!(params.str("name"), Err(AttributeError::MissingArgument("name")));
+    assert_eq!(params.float("speed"), Ok(1.5));
+
+    let params = AttributeParams::resolve(
+      SCHEMA,
+      vec![
+        ParseArgumentItem::Number(3),
+        ParseArgumentItem::Number(1),
+        ParseArgumentItem::Str("a".to_string()),
+        ParseArgumentItem::Number(2),
+      ],
+    )
+    .unwrap();
+    assert_eq!(params.float("speed"), Ok(2.0));
+  }
+
+  #[test]
+  fn test_resolve_keywords() {
+    let keyword = |name: &str, value| ParseArgumentItem::Keyword(name.to_string(), Box::new(value));
+
+    let params = AttributeParams::resolve(
+      SCHEMA,
+      vec![
+        ParseArgumentItem::Number(1),
+        keyword("speed", ParseArgumentItem::Float(0.5)),
+        keyword("x", ParseArgumentItem::Number(4)),
+      ],
+    )
+    .unwrap();
+    assert_eq!(params.number("id"), Ok(1));
+    assert_eq!(params.number("x"), Ok(4));
+    assert_eq!(params.float("speed"), Ok(0.5));
+    assert!(!params.is_set("name"));
+
+    assert_eq!(
+      AttributeParams::resolve(SCHEMA, vec![keyword("z", ParseArgumentItem::Number(1))]),
+      Err(AttributeError::UnknownParameter("z".to_string()))
+    );
+    assert_eq!(
+      AttributeParams::resolve(
+        SCHEMA,
+        vec![
+          ParseArgumentItem::Number(1),
+          keyword("id", ParseArgumentItem::Number(2))
+        ]
+      ),
+      Err(AttributeError::DuplicateArgument("id"))
+    );
+    assert_eq!(
+      AttributeParams::resolve(SCHEMA, vec![keyword("x", ParseArgumentItem::Number(2))]),
+      Err(AttributeError::MissingArgument("id"))
+    );
+  }
+
+  #[test]
+  fn test_resolve_errors() {
+    assert_eq!(
+      AttributeParams::resolve(SCHEMA, vec![]),
+      Err(AttributeError::MissingArgument("id"))
+    );
+    assert_eq!(
+      AttributeParams::resolve(SCHEMA, vec![ParseArgumentItem::Bool(true)]),
+      Err(AttributeError::WrongType {
+        param: "id",
+        expected: ParamKind::Number,
+        found: ParseArgumentItem::Bool(true)
+      })
+    );
+    assert_eq!(
+      AttributeParams::resolve(SCHEMA, vec![ParseArgumentItem::Number(0); 5]),
+      Err(AttributeError::TooManyArguments { expected: 4, found: 5 })
+    );
+
+    let params = AttributeParams::resolve(SCHEMA, vec![ParseArgumentItem::Number(-1)]).unwrap();
+    assert!(params.unsigned("id").is_err());
+  }
+
+  #[test]
+  fn test_resolve_ability() {
+    const ABILITY_SCHEMA: &[ParamSpec] = &[ParamSpec::required("attribute", ParamKind::Ability)];
+
+    let params = AttributeParams::resolve(ABILITY_SCHEMA, vec![ParseArgumentItem::Str("dash".to_string())]).unwrap();
+    assert_eq!(params.ability("attribute"), Ok(("dash", &[][..])));
+
+    let args = vec![ParseArgumentItem::Number(2)];
+    let params = AttributeParams::resolve(
+      ABILITY_SCHEMA,
+      vec![ParseArgumentItem::Call("dash".to_string(), args.clone())],
+    )
+    .unwrap();
+    assert_eq!(params.ability("attribute"), Ok(("dash", args.as_slice())));
+
+    assert!(AttributeParams::resolve(ABILITY_SCHEMA, vec![ParseArgumentItem::Number(2)]).is_err());
+  }
+
+  #[test]
+  fn test_resolve_list() {
+    const POINT_SCHEMA: &[ParamSpec] = &[ParamSpec::required("x", ParamKind::Number)];
+    const POINT: ParamKind = ParamKind::Call("p", POINT_SCHEMA);
+    const LIST_SCHEMA: &[ParamSpec] = &[ParamSpec::list("points", &POINT), ParamSpec::number("speed", 1)];
+    let point = |x| ParseArgumentItem::Call("p".to_string(), vec![ParseArgumentItem::Number(x)]);
+
+    let params = AttributeParams::resolve(LIST_SCHEMA, vec![point(1), point(2)]).unwrap();
+    assert_eq!(params.list("points").map(|points| points.len()), Ok(2));
+    assert_eq!(params.number("speed"), Ok(1));
+    let points = params.calls("points", POINT_SCHEMA).unwrap();
+    assert_eq!(points[1].number("x"), Ok(2));
+
+    let speed = ParseArgumentItem::Keyword("speed".to_string(), Box::new(ParseArgumentItem::Number(3)));
+    let params = AttributeParams::resolve(LIST_SCHEMA, vec![point(1), speed]).unwrap();
+    assert_eq!(params.number("speed"), Ok(3));
+
+    assert_eq!(
+      AttributeParams::resolve(LIST_SCHEMA, vec![]),
+      Err(AttributeError::MissingArgument("points"))
+    );
+    assert!(AttributeParams::resolve(LIST_SCHEMA, vec![point(1), ParseArgumentItem::Number(3)]).is_err());
+    assert!(AttributeParams::resolve(LIST_SCHEMA, vec![ParseArgumentItem::Call("p".to_string(), vec![])]).is_err());
+  }
+}
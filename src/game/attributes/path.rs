@@ -0,0 +1,388 @@
+//! Moves a sprite through a list of waypoints. `path(points..., segment,
+//! mode, ease, pause, phase, group)`
+//!
+//! # Usage
+//! `points`: Waypoints relative to the sprite's origin in tiles, each written
+//! as `p(x, y)`, e.g. `path(p(3, 0), p(3, 2))`. A waypoint may have a third
+//! value to pause there for that many seconds, e.g. `p(3, 0, 1.5)`. All other
+//! parameters must be given by name, e.g. `path(p(3, 0), segment=2)`.
+//!
+//! `segment`: Seconds to travel between two waypoints (default 1)
+//!
+//! `mode`: `loop` back to the origin after the last waypoint, or `pingpong`
+//! back through the waypoints in reverse (default loop)
+//!
+//! `ease`: Ease in and out of each waypoint rather than moving linearly
+//! (default false)
+//!
+//! `pause`: Seconds to pause at the origin and any waypoint without its own
+//! pause (default 0)
+//!
+//! `phase` and `group`: As with [MovingSprite](super::MovingSprite), where
+//! `phase` is the fraction of the whole cycle, including pauses.
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use super::{
+  Attribute, AttributeError, AttributeParams, MovementGroup, MovementGroups, ParamKind, ParamSpec, PlatformDelta,
+};
+use crate::game::physics::PhysicsCommands;
+use crate::level::LevelId;
+
+/// What a path does after reaching its last waypoint.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PathMode {
+  Loop,
+  PingPong,
+}
+
+impl PathMode {
+  pub fn from_param(value: &str) -> Option<Self> {
+    match value {
+      "loop" => Some(Self::Loop),
+      "pingpong" => Some(Self::PingPong),
+      _ => None,
+    }
+  }
+}
+
+/// Point along a path, relative to the sprite's origin in tiles.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Waypoint {
+  pub offset: Vec2,
+  /// Seconds to wait once the waypoint is reached. Uses the path's default
+  /// pause if not given.
+  pub pause: Option<f32>,
+}
+
+impl Waypoint {
+  /// Parameters of a single waypoint, `p(x, y, pause)`.
+  pub const PARAMS: &'static [ParamSpec] = &[
+    ParamSpec::required("x", ParamKind::Float),
+    ParamSpec::required("y", ParamKind::Float),
+    ParamSpec::optional("pause", ParamKind::Float),
+  ];
+
+  /// Reads the waypoints of a list parameter of `p` calls.
+  pub fn from_params(params: &AttributeParams, name: &'static str) -> Result<Vec<Self>, AttributeError> {
+    params
+      .calls(name, Self::PARAMS)?
+      .iter()
+      .map(|point| {
+        let pause = if point.is_set("pause") {
+          Some(point.float("pause")?)
+        } else {
+          None
+        };
+        if pause.map_or(false, |pause| pause < 0.0) {
+          return Err(AttributeError::invalid(name, "has a waypoint with a negative pause"));
+        }
+
+        Ok(Self {
+          offset: Vec2::new(point.float("x")?, point.float("y")?),
+          pause,
+        })
+      })
+      .collect()
+  }
+}
+
+/// Part of a path cycle, either travelling between two waypoints or pausing
+/// at one (when `from == to`).
+#[derive(Clone, Copy, Debug)]
+struct PathLeg {
+  from: Vec2,
+  to: Vec2,
+  duration: f32,
+  eased: bool,
+}
+
+impl PathLeg {
+  fn offset_at(&self, time: f32) -> Vec2 {
+    let t = (time / self.duration).clamp(0.0, 1.0);
+    let t = if self.eased { t * t * (3.0 - 2.0 * t) } else { t };
+    self.from.lerp(self.to, t)
+  }
+}
+
+/// `path` attribute state.
+#[derive(Component)]
+pub struct PathSprite {
+  starting_position: Vec2,
+  legs: Vec<PathLeg>,
+  cycle_duration: f32,
+  current_time: f32,
+  time_offset: f32,
+  offset: Vec2,
+  player_delta: Vec2,
+}
+
+impl PathSprite {
+  pub fn new(
+    waypoints: &[Waypoint],
+    segment_duration: f32,
+    mode: PathMode,
+    eased: bool,
+    default_pause: f32,
+    phase: f32,
+    position: Vec2,
+  ) -> Self {
+    let origin = Waypoint {
+      offset: Vec2::ZERO,
+      pause: Some(default_pause),
+    };
+
+    // Visit order of waypoints through one cycle, ending back at the origin
+    let mut route = vec![origin];
+    route.extend_from_slice(waypoints);
+    match mode {
+      PathMode::Loop => route.push(origin),
+      PathMode::PingPong => route.extend(waypoints.iter().rev().skip(1).copied().chain([origin])),
+    }
+
+    let mut legs = Vec::new();
+    for pair in route.windows(2) {
+      let (from, to) = (pair[0], pair[1]);
+      legs.push(PathLeg {
+        from: from.offset,
+        to: to.offset,
+        duration: segment_duration,
+        eased,
+      });
+
+      let pause = to.pause.unwrap_or(default_pause);
+      if pause > 0.0 {
+        legs.push(PathLeg {
+          from: to.offset,
+          to: to.offset,
+          duration: pause,
+          eased: false,
+        });
+      }
+    }
+
+    let cycle_duration = legs.iter().map(|leg| leg.duration).sum();
+    let mut path = Self {
+      starting_position: position,
+      cycle_duration,
+      legs,
+      current_time: 0.0,
+      time_offset: phase * cycle_duration,
+      offset: Vec2::ZERO,
+      player_delta: Vec2::ZERO,
+    };
+    path.reset();
+    path
+  }
+
+  /// Offset from the origin at a point in time through the cycle.
+  fn offset_at(&self, time: f32) -> Vec2 {
+    let mut time = time.rem_euclid(self.cycle_duration);
+    for leg in self.legs.iter() {
+      if time < leg.duration {
+        return leg.offset_at(time);
+      }
+      time -= leg.duration;
+    }
+
+    Vec2::ZERO
+  }
+
+  /// Increments time and recalculates the sprite's offset
+  pub fn increment_time(&mut self, delta_t: f32) {
+    self.set_time((self.current_time + delta_t).rem_euclid(self.cycle_duration));
+  }
+
+  /// Sets time, such as from a [GroupClock](super::GroupClock), and
+  /// recalculates the sprite's offset
+  pub fn set_time(&mut self, time: f32) {
+    self.current_time = time;
+    let offset = self.offset_at(self.current_time + self.time_offset);
+    self.player_delta = offset - self.offset;
+    self.offset = offset;
+  }
+
+  /// Returns to the start of the cycle without carrying a riding player.
+  pub fn reset(&mut self) {
+    self.set_time(0.0);
+    self.player_delta = Vec2::ZERO;
+  }
+
+  pub fn player_delta(&self) -> Vec2 {
+    self.player_delta
+  }
+
+  /// Returns the position of the sprite, per current time
+  pub fn get_position(&self) -> Vec2 {
+    self.starting_position + self.offset
+  }
+}
+
+impl Attribute for PathSprite {
+  const KEY: &'static str = "path";
+  const PARAMS: &'static [ParamSpec] = &[
+    ParamSpec::list("points", &ParamKind::Call("p", Waypoint::PARAMS)),
+    ParamSpec::float("segment", 1.0),
+    ParamSpec::string("mode", "loop"),
+    ParamSpec::boolean("ease", false),
+    ParamSpec::float("pause", 0.0),
+    ParamSpec::float("phase", 0.0),
+    ParamSpec::optional("group", ParamKind::Number),
+  ];
+
+  fn build(
+    commands: &mut Commands,
+    target: Entity,
+    _: LevelId,
+    position: Vec2,
+    params: AttributeParams,
+  ) -> Result<(), AttributeError> {
+    let waypoints = Waypoint::from_params(&params, "points")?;
+    let segment = params.float("segment")?;
+    let mode = PathMode::from_param(params.str("mode")?)
+      .ok_or_else(|| AttributeError::invalid("mode", "must be one of loop or pingpong"))?;
+    let pause = params.float("pause")?;
+    let group = MovementGroup::from_params(&params)?;
+
+    if segment <= 0.0 {
+      return Err(AttributeError::invalid("segment", "must be greater than zero"));
+    }
+    if pause < 0.0 {
+      return Err(AttributeError::invalid("pause", "must not be negative"));
+    }
+
+    commands
+      .entity(target)
+      .insert(ColliderPositionSync::Discrete)
+      .insert(PlatformDelta::default())
+      .insert(PathSprite::new(
+        &waypoints,
+        segment,
+        mode,
+        params.bool("ease")?,
+        pause,
+        params.float("phase")?,
+        position,
+      ));
+
+    if let Some(group) = group {
+      commands.entity(target).insert(group);
+    }
+
+    Ok(())
+  }
+}
+
+/// System to move all path sprites per change in [Time].
+pub fn path_system(
+  time: Res<Time>,
+  physics_commands: Res<PhysicsCommands>,
+  groups: Res<MovementGroups>,
+  mut path_sprite: Query<(
+    &mut PathSprite,
+    Option<&MovementGroup>,
+    &mut PlatformDelta,
+    &mut ColliderPositionComponent,
+  )>,
+) {
+  if physics_commands.paused() {
+    return;
+  }
+
+  path_sprite.for_each_mut(|(mut path, group, mut platform_delta, mut collider_position)| {
+    match group {
+      Some(group) => path.set_time(groups.time(group.0)),
+      None => path.increment_time(time.delta().as_secs_f32()),
+    }
+    platform_delta.0 = path.player_delta();
+    collider_position.0 = path.get_position().into();
+  });
+}
+
+#[cfg(test)]
+mod tests {
+  use bevy::prelude::*;
+
+  use crate::game::attributes::lex::AttributeEntry;
+  use crate::game::attributes::*;
+
+  fn parse_waypoints(attribute: &str) -> Result<Vec<Waypoint>, AttributeError> {
+    let entry = AttributeEntry::parse(attribute).unwrap();
+    let params = AttributeParams::resolve(PathSprite::PARAMS, entry.1)?;
+    Waypoint::from_params(&params, "points")
+  }
+
+  #[test]
+  fn test_path_waypoints() {
+    assert_eq!(
+      parse_waypoints("path(p(3, 0), p(3, 2.5, 1), segment=2)"),
+      Ok(vec![
+        Waypoint {
+          offset: Vec2::new(3.0, 0.0),
+          pause: None
+        },
+        Waypoint {
+          offset: Vec2::new(3.0, 2.5),
+          pause: Some(1.0)
+        },
+      ])
+    );
+    assert_eq!(
+      parse_waypoints("path(points=p(1, 1))").map(|points| points.len()),
+      Ok(1)
+    );
+    assert_eq!(
+      parse_waypoints("path()"),
+      Err(AttributeError::MissingArgument("points"))
+    );
+    assert!(parse_waypoints("path(p(3))").is_err());
+    assert!(parse_waypoints("path(p(3, a))").is_err());
+    assert!(parse_waypoints("path(q(3, 0))").is_err());
+    assert!(parse_waypoints("path(p(1, 1, -1))").is_err());
+    assert!(parse_waypoints("path(p(1, 1), 2)").is_err());
+  }
+
+  #[test]
+  fn test_path_loop() {
+    let waypoints = parse_waypoints("path(p(2, 0, 1), p(2, 2))").unwrap();
+    let mut path = PathSprite::new(&waypoints, 1.0, PathMode::Loop, false, 0.0, 0.0, Vec2::ZERO);
+
+    path.increment_time(0.5);
+    assert_eq!(path.get_position(), Vec2::new(1.0, 0.0));
+    assert_eq!(path.player_delta(), Vec2::new(1.0, 0.0));
+
+    // Paused at the first waypoint
+    path.increment_time(1.0);
+    assert_eq!(path.get_position(), Vec2::new(2.0, 0.0));
+    path.increment_time(0.5);
+    assert_eq!(path.get_position(), Vec2::new(2.0, 0.0));
+
+    path.increment_time(1.5);
+    assert_eq!(path.get_position(), Vec2::new(1.0, 1.0));
+
+    // Back at the origin after a full cycle
+    path.increment_time(0.5);
+    assert_eq!(path.get_position(), Vec2::ZERO);
+  }
+
+  #[test]
+  fn test_path_pingpong() {
+    let waypoints = parse_waypoints("path(p(2, 0), p(4, 0))").unwrap();
+    let mut path = PathSprite::new(&waypoints, 1.0, PathMode::PingPong, true, 0.0, 0.0, Vec2::ONE);
+
+    path.increment_time(2.0);
+    assert_eq!(path.get_position(), Vec2::new(5.0, 1.0));
+    path.increment_time(0.5);
+    assert_eq!(path.get_position(), Vec2::new(4.0, 1.0));
+    path.increment_time(1.5);
+    assert_eq!(path.get_position(), Vec2::ONE);
+
+    // Half way through the cycle, at the last waypoint
+    let mut path = PathSprite::new(&waypoints, 1.0, PathMode::PingPong, true, 0.0, 0.5, Vec2::ONE);
+    assert_eq!(path.get_position(), Vec2::new(5.0, 1.0));
+    assert_eq!(path.player_delta(), Vec2::ZERO);
+    path.set_time(1.0);
+    assert_eq!(path.get_position(), Vec2::new(3.0, 1.0));
+  }
+}
@@ -0,0 +1,57 @@
+//! Solid pressure plate, activating a signal channel while the player stands
+//! on it. `plate(channel)`
+
+use bevy::prelude::*;
+
+use super::solid::solid_collider;
+use super::{Attribute, AttributeError, AttributeParams, ParamKind, ParamSpec, Player};
+use crate::game::signal::{SignalChannel, SignalCommands};
+use crate::level::LevelId;
+
+#[derive(Component)]
+pub struct Plate {
+  pub channel: SignalChannel,
+  pressed: bool,
+}
+
+impl Attribute for Plate {
+  const KEY: &'static str = "plate";
+  const PARAMS: &'static [ParamSpec] = &[ParamSpec::required("channel", ParamKind::Number)];
+
+  fn build(
+    commands: &mut Commands,
+    target: Entity,
+    _: LevelId,
+    position: Vec2,
+    params: AttributeParams,
+  ) -> Result<(), AttributeError> {
+    let channel = params.unsigned("channel")?;
+
+    commands
+      .entity(target)
+      .insert(Plate {
+        channel,
+        pressed: false,
+      })
+      .insert_bundle(solid_collider(position));
+
+    Ok(())
+  }
+}
+
+/// Sets the channel of each plate as the player steps on and off it.
+pub fn plate_system(
+  player: Query<&Player>,
+  mut plates: Query<(Entity, &mut Plate)>,
+  mut signal_commands: ResMut<SignalCommands>,
+) {
+  let ground_entity = player.get_single().ok().and_then(|player| player.ground_entity);
+
+  plates.for_each_mut(|(ent, mut plate)| {
+    let pressed = ground_entity == Some(ent);
+    if pressed != plate.pressed {
+      plate.pressed = pressed;
+      signal_commands.set(plate.channel, pressed);
+    }
+  });
+}
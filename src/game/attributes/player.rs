@@ -3,8 +3,7 @@
 use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
 
-use super::lex::ParseArgumentItem;
-use super::Attribute;
+use super::{Attribute, AttributeError, AttributeParams};
 use crate::game::collision_groups::*;
 use crate::level::LevelId;
 
@@ -17,14 +16,33 @@ pub struct Player {
   pub jump_in_progress: bool,
   pub outside_ground_bounds: bool,
   pub on_moving_entity: Option<Entity>,
+  /// Entity the player is grounded on, if any.
+  pub ground_entity: Option<Entity>,
   pub respawn_level: LevelId,
   pub respawn_pos: Vec2,
+  /// Force applied by the zones the player is in (see [super::zone_system]).
+  pub zone_force: Vec2,
+  /// Gravity multiplier of the zones the player is in.
+  pub zone_gravity: f32,
+  /// Whether the player is swimming in a `water` zone.
+  pub in_water: bool,
+  /// Speed of the `conveyor` the player is grounded on, in tiles per second.
+  pub ground_conveyor: f32,
+  /// Grip of the surface the player is grounded on (see `friction`).
+  pub ground_friction: f32,
+  /// Whether the player's gravity is inverted (see `flip_gravity`).
+  pub flipped: bool,
+  /// Whether the player's gravity was inverted at the respawn point.
+  pub respawn_flipped: bool,
 }
 
 impl Player {
   pub const JUMP_BOOST_TIME: f32 = 0.35;
   pub const NORMAL_FALL_SPEED: f32 = 2.25;
   pub const SLOW_FALL_SPEED: f32 = 1.25;
+  pub const WATER_FALL_SPEED: f32 = 0.4;
+  pub const AIR_DAMPING: f32 = 1.5;
+  pub const WATER_DAMPING: f32 = 4.0;
 
   pub fn new(respawn_level: LevelId, respawn_pos: Vec2) -> Self {
     Self {
@@ -34,16 +52,54 @@ impl Player {
       jump_in_progress: false,
       outside_ground_bounds: false,
       on_moving_entity: None,
+      ground_entity: None,
       respawn_level,
       respawn_pos,
+      zone_force: Vec2::ZERO,
+      zone_gravity: 1.0,
+      in_water: false,
+      ground_conveyor: 0.0,
+      ground_friction: 1.0,
+      flipped: false,
+      respawn_flipped: false,
     }
   }
+
+  /// Vertical direction away from the player's gravity, 1 or -1 if flipped.
+  pub fn up(&self) -> f32 {
+    if self.flipped {
+      -1.0
+    } else {
+      1.0
+    }
+  }
+
+  /// Inverts the player's gravity, leaving the ground they hovered over.
+  pub fn flip(&mut self) {
+    self.flipped = !self.flipped;
+    self.grounded = false;
+    self.leave_ground();
+  }
+
+  /// Forgets the ground the player hovered over, along with its surface.
+  pub fn leave_ground(&mut self) {
+    self.ground_entity = None;
+    self.on_moving_entity = None;
+    self.ground_conveyor = 0.0;
+    self.ground_friction = 1.0;
+  }
 }
 
 impl Attribute for Player {
   const KEY: &'static str = "player";
 
-  fn build(commands: &mut Commands, target: Entity, level: LevelId, position: Vec2, _: Vec<ParseArgumentItem>) {
+  fn build(
+    commands: &mut Commands,
+    target: Entity,
+    level: LevelId,
+    position: Vec2,
+    _: AttributeParams,
+  ) -> Result<(), AttributeError> {
     let rigid_body = RigidBodyBundle {
       position: position.into(),
       mass_properties: (RigidBodyMassPropsFlags::ROTATION_LOCKED).into(),
@@ -53,7 +109,7 @@ impl Attribute for Player {
       }
       .into(),
       damping: RigidBodyDamping {
-        linear_damping: 1.5,
+        linear_damping: Self::AIR_DAMPING,
         ..Default::default()
       }
       .into(),
@@ -82,5 +138,7 @@ impl Attribute for Player {
       .insert_bundle(rigid_body)
       .insert_bundle(collider)
       .insert(RigidBodyPositionSync::Interpolated { prev_pos: None });
+
+    Ok(())
   }
 }
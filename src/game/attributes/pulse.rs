@@ -0,0 +1,153 @@
+//! Deadly sprite that periodically retracts, such as spikes or flame jets.
+//! `pulse(on_secs, off_secs, optional phase)`
+//!
+//! # Usage
+//! `on_secs`: Seconds the sprite is shown and deadly each cycle
+//!
+//! `off_secs`: Seconds the sprite is hidden and harmless each cycle
+//!
+//! `phase`: Fraction of the cycle the sprite starts at, e.g. `0.5` with equal
+//! times starts off (default 0)
+//!
+//! The sprite's collider is removed from [DEADLY_GROUP] while off, so it can't
+//! be touched, and the timer stops while physics is paused.
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use super::{Attribute, AttributeError, AttributeParams, Deadly, ParamKind, ParamSpec};
+use crate::game::collision::ContactSubscription;
+use crate::game::collision_groups::*;
+use crate::game::physics::PhysicsCommands;
+use crate::level::LevelId;
+
+#[derive(Component)]
+pub struct Pulse {
+  pub on_secs: f32,
+  pub off_secs: f32,
+  /// Seconds into the current cycle.
+  time: f32,
+}
+
+impl Pulse {
+  pub fn new(on_secs: f32, off_secs: f32, phase: f32) -> Self {
+    Self {
+      on_secs,
+      off_secs,
+      time: phase.rem_euclid(1.0) * (on_secs + off_secs),
+    }
+  }
+
+  /// Whether the sprite is deadly at this point in the cycle.
+  pub fn active(&self) -> bool {
+    self.time < self.on_secs
+  }
+
+  pub fn tick(&mut self, delta: f32) {
+    self.time = (self.time + delta) % (self.on_secs + self.off_secs);
+  }
+}
+
+impl Attribute for Pulse {
+  const KEY: &'static str = "pulse";
+  const PARAMS: &'static [ParamSpec] = &[
+    ParamSpec::required("on_secs", ParamKind::Float),
+    ParamSpec::required("off_secs", ParamKind::Float),
+    ParamSpec::float("phase", 0.0),
+  ];
+
+  fn build(
+    commands: &mut Commands,
+    target: Entity,
+    _: LevelId,
+    position: Vec2,
+    params: AttributeParams,
+  ) -> Result<(), AttributeError> {
+    let on_secs = params.float("on_secs")?;
+    let off_secs = params.float("off_secs")?;
+    let phase = params.float("phase")?;
+
+    if on_secs < 0.0 {
+      return Err(AttributeError::invalid("on_secs", "must not be negative"));
+    }
+    if off_secs < 0.0 {
+      return Err(AttributeError::invalid("off_secs", "must not be negative"));
+    }
+    if on_secs + off_secs <= 0.0 {
+      return Err(AttributeError::invalid(
+        "on_secs",
+        "cycle must be longer than zero seconds",
+      ));
+    }
+
+    let pulse = Pulse::new(on_secs, off_secs, phase);
+    let collision_groups = if pulse.active() { DEADLY_GROUP } else { NONE_GROUP };
+
+    let collider = ColliderBundle {
+      position: position.into(),
+      shape: ColliderShape::cuboid(0.5, 0.5).into(),
+      material: ColliderMaterialComponent::default(),
+      flags: ColliderFlags {
+        collision_groups,
+        solver_groups: NONE_GROUP,
+        active_events: ActiveEvents::CONTACT_EVENTS,
+        ..Default::default()
+      }
+      .into(),
+      ..Default::default()
+    };
+
+    commands
+      .entity(target)
+      .insert(pulse)
+      .insert(Deadly)
+      .insert(ContactSubscription)
+      .insert_bundle(collider)
+      .insert(ColliderPositionSync::Discrete);
+
+    Ok(())
+  }
+}
+
+/// Advances pulse timers, switching the deadly collider and visibility of
+/// each sprite as it turns on or off.
+pub fn pulse_system(
+  time: Res<Time>,
+  physics_commands: Res<PhysicsCommands>,
+  mut pulses: Query<(&mut Pulse, &mut ColliderFlagsComponent, &mut Visibility)>,
+) {
+  let paused = physics_commands.paused();
+
+  pulses.for_each_mut(|(mut pulse, mut flags, mut visibility)| {
+    if !paused {
+      pulse.tick(time.delta_seconds());
+    }
+
+    let active = pulse.active();
+    if visibility.is_visible != active {
+      visibility.is_visible = active;
+      flags.collision_groups = if active { DEADLY_GROUP } else { NONE_GROUP };
+    }
+  });
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::game::attributes::*;
+
+  #[test]
+  fn test_pulse_cycle() {
+    let mut pulse = Pulse::new(1.0, 2.0, 0.0);
+    assert!(pulse.active());
+    pulse.tick(1.5);
+    assert!(!pulse.active());
+    pulse.tick(1.5);
+    assert!(pulse.active());
+
+    // Phase is a fraction of the whole cycle
+    let pulse = Pulse::new(1.0, 1.0, 0.5);
+    assert!(!pulse.active());
+    let pulse = Pulse::new(1.0, 1.0, 1.25);
+    assert!(pulse.active());
+  }
+}
@@ -0,0 +1,100 @@
+//! Restores the player's dash charges on contact, including mid-air.
+//! `refill(optional respawn delay in seconds)`
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use super::give::pickup_collider;
+use super::{Attribute, AttributeError, AttributeParams, Dash, ParamSpec, Player};
+use crate::game::collision::{ContactSubscription, PlayerContacted};
+use crate::game::orbs::PlayerOrbCommands;
+use crate::game::physics::PhysicsCommands;
+use crate::level::LevelId;
+
+#[derive(Component)]
+pub struct Refill {
+  /// Seconds until the refill reappears after being used.
+  respawn_delay: f32,
+  /// Seconds left until the refill reappears, zero while it is available.
+  cooldown: f32,
+}
+
+impl Refill {
+  pub fn available(&self) -> bool {
+    self.cooldown <= 0.0
+  }
+}
+
+impl Attribute for Refill {
+  const KEY: &'static str = "refill";
+  const PARAMS: &'static [ParamSpec] = &[ParamSpec::float("delay", 2.5)];
+
+  fn build(
+    commands: &mut Commands,
+    target: Entity,
+    _: LevelId,
+    position: Vec2,
+    params: AttributeParams,
+  ) -> Result<(), AttributeError> {
+    let respawn_delay = params.float("delay")?;
+    if respawn_delay < 0.0 {
+      return Err(AttributeError::invalid("delay", "must not be negative"));
+    }
+
+    commands
+      .entity(target)
+      .insert(Refill {
+        respawn_delay,
+        cooldown: 0.0,
+      })
+      .insert(ContactSubscription)
+      .insert_bundle(pickup_collider(position))
+      .insert(ColliderPositionSync::Discrete);
+
+    Ok(())
+  }
+}
+
+/// Consumes [PlayerContacted] tags, restoring dash charges if the player is
+/// missing any.
+pub fn on_refill_system(
+  mut commands: Commands,
+  mut refill_contacted: Query<(Entity, &mut Refill, &mut Visibility), With<PlayerContacted>>,
+  mut dash: Query<&mut Dash, With<Player>>,
+  mut orb_commands: ResMut<PlayerOrbCommands>,
+) {
+  refill_contacted.for_each_mut(|(ent, mut refill, mut visibility)| {
+    if refill.available() {
+      if let Ok(mut dash) = dash.get_single_mut() {
+        if dash.reset_charges() {
+          orb_commands.set_dash_count(dash.charges().try_into().unwrap());
+          refill.cooldown = refill.respawn_delay;
+          visibility.is_visible = refill.available();
+        }
+      }
+    }
+
+    commands.entity(ent).remove::<PlayerContacted>();
+  });
+}
+
+/// Ticks down used refills, making them available again once their delay has
+/// passed. Paused while physics is paused.
+pub fn refill_respawn_system(
+  time: Res<Time>,
+  physics_commands: Res<PhysicsCommands>,
+  mut refills: Query<(&mut Refill, &mut Visibility)>,
+) {
+  if physics_commands.paused() {
+    return;
+  }
+
+  refills.for_each_mut(|(mut refill, mut visibility)| {
+    if !refill.available() {
+      refill.cooldown -= time.delta_seconds();
+      if refill.available() {
+        visibility.is_visible = true;
+      }
+    }
+  });
+}
@@ -0,0 +1,382 @@
+//! Runtime registry of attributes.
+//!
+//! Attributes are looked up by key in the [AttributeRegistry] resource when a
+//! level is loaded or validated. Temple's own attributes are registered by
+//! [AttributeRegistryPlugin], and games can add their own from any [Plugin]
+//! using [RegisterAttribute::register_attribute]:
+//!
+//! ```ignore
+//! impl Plugin for MyGamePlugin {
+//!   fn build(&self, app: &mut App) {
+//!     app.register_attribute::<Bouncy>();
+//!   }
+//! }
+//! ```
+
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+
+use super::lex::{AttributeEntry, ParseArgumentItem};
+use super::{
+  Ambience, Attribute, AttributeBuildError, AttributeError, AttributeParams, Bounce, CameraZone, Checkpoint, Collect,
+  Conveyor, Crumble, Dash, Deadly, Dialogue, Door, Enemy, Falling, Flip, FlipGravity, Focus, ForceZone, Friction,
+  GivableAttribute, Goal, GravityZone, GroupControl, Key, Lock, MovingSprite, MusicTrigger, ParamDefault, ParamKind,
+  ParamSpec, PathSprite, Plate, Player, Pulse, Refill, Semisolid, Shooter, Sign, Solid, Switch, TakeableAttribute,
+  TimerSwitch, Transition, Water,
+};
+use crate::level::LevelId;
+
+/// Builds an attribute onto an [Entity] from its resolved parameters.
+pub type AttributeBuilder = fn(&mut Commands, Entity, LevelId, Vec2, AttributeParams) -> Result<(), AttributeError>;
+
+/// Checks an attribute's resolved parameters (see [Attribute::validate]).
+pub type AttributeValidator = fn(&AttributeParams) -> Result<(), AttributeError>;
+
+/// Removes an attribute from an [Entity].
+pub type AttributeRemover = fn(&mut Commands, Entity);
+
+/// Registered attribute, as its key, parameter schema and builder.
+#[derive(Clone, Copy)]
+pub struct AttributeRegistration {
+  pub key: &'static str,
+  pub params: &'static [ParamSpec],
+  /// See [Attribute::ABILITY]
+  pub ability: bool,
+  pub build: AttributeBuilder,
+  pub validate: AttributeValidator,
+  pub remove: AttributeRemover,
+}
+
+impl AttributeRegistration {
+  pub fn of<A: Attribute>() -> Self {
+    Self {
+      key: A::KEY,
+      params: A::PARAMS,
+      ability: A::ABILITY,
+      build: A::build,
+      validate: A::validate,
+      remove: A::remove,
+    }
+  }
+
+  /// Human readable signature, e.g. `goal(exit: whole number)`
+  pub fn signature(&self) -> String {
+    let params: Vec<_> = self
+      .params
+      .iter()
+      .map(|spec| {
+        if spec.default == ParamDefault::Required {
+          format!("{}: {}", spec.name, spec.kind)
+        } else {
+          format!("{}?: {}", spec.name, spec.kind)
+        }
+      })
+      .collect();
+    format!("{}({})", self.key, params.join(", "))
+  }
+
+  /// Attribute string with placeholders for each required parameter, used as
+  /// a starting point in the editor.
+  pub fn template(&self) -> String {
+    let params = template_arguments(self.params);
+    if params.is_empty() {
+      self.key.to_string()
+    } else {
+      format!("{}({})", self.key, params.join(", "))
+    }
+  }
+
+  /// Resolves arguments against this attribute's schema, and checks them with
+  /// [Attribute::validate].
+  pub fn resolve(&self, args: Vec<ParseArgumentItem>) -> Result<AttributeParams, AttributeError> {
+    let params = AttributeParams::resolve(self.params, args)?;
+    (self.validate)(&params)?;
+    Ok(params)
+  }
+}
+
+/// Keyword arguments with a placeholder value for each required parameter.
+fn template_arguments(params: &[ParamSpec]) -> Vec<String> {
+  params
+    .iter()
+    .filter(|spec| spec.default == ParamDefault::Required)
+    .map(|spec| format!("{}={}", spec.name, template_placeholder(&spec.kind)))
+    .collect()
+}
+
+fn template_placeholder(kind: &ParamKind) -> String {
+  match kind {
+    ParamKind::Number => "0".to_string(),
+    ParamKind::Float => "0.0".to_string(),
+    ParamKind::Str => "\"\"".to_string(),
+    ParamKind::Bool => "false".to_string(),
+    ParamKind::Ability => Dash::KEY.to_string(),
+    ParamKind::Call(key, params) => format!("{}({})", key, template_arguments(params).join(", ")),
+    ParamKind::List(kind) => template_placeholder(kind),
+  }
+}
+
+/// [Res] of all attributes that can be used by sprites, by key.
+#[derive(Default)]
+pub struct AttributeRegistry(BTreeMap<&'static str, AttributeRegistration>);
+
+impl AttributeRegistry {
+  /// Registers an attribute. Registering a key a second time replaces the
+  /// previous attribute.
+  pub fn register<A: Attribute>(&mut self) -> &mut Self {
+    if self.0.insert(A::KEY, AttributeRegistration::of::<A>()).is_some() {
+      warn!(target: "AttributeRegistry", "Attribute `{}` was registered twice, replacing it.", A::KEY);
+    }
+    self
+  }
+
+  pub fn get(&self, key: &str) -> Option<&AttributeRegistration> {
+    self.0.get(key)
+  }
+
+  /// Iterates over all registered attributes, ordered by key.
+  pub fn iter(&self) -> impl Iterator<Item = &AttributeRegistration> {
+    self.0.values()
+  }
+
+  /// Parses an attribute string and finds its registration.
+  fn lookup(&self, attribute: &str) -> Result<(&AttributeRegistration, Vec<ParseArgumentItem>), AttributeError> {
+    let entry = AttributeEntry::parse(attribute).map_err(AttributeError::Syntax)?;
+    self
+      .get(entry.0.as_str())
+      .map(|registration| (registration, entry.1))
+      .ok_or(AttributeError::UnknownAttribute(entry.0))
+  }
+
+  /// Resolves arguments for a registered attribute, including any abilities
+  /// given as arguments, e.g. `dash(2)` in `give(dash(2))`.
+  fn resolve(
+    &self,
+    registration: &AttributeRegistration,
+    args: Vec<ParseArgumentItem>,
+  ) -> Result<AttributeParams, AttributeError> {
+    let params = registration.resolve(args)?;
+
+    for spec in registration
+      .params
+      .iter()
+      .filter(|spec| spec.kind == ParamKind::Ability)
+    {
+      if params.is_set(spec.name) {
+        let (key, args) = params.ability(spec.name)?;
+        self
+          .resolve_ability(key, args.to_vec())
+          .map_err(|err| AttributeError::invalid(spec.name, format!("is not a valid ability, {}", err)))?;
+      }
+    }
+
+    Ok(params)
+  }
+
+  /// Finds an attribute that can be given to the player and resolves its
+  /// arguments.
+  pub fn resolve_ability(
+    &self,
+    key: &str,
+    args: Vec<ParseArgumentItem>,
+  ) -> Result<(&AttributeRegistration, AttributeParams), AttributeError> {
+    let registration = self
+      .get(key)
+      .ok_or_else(|| AttributeError::UnknownAttribute(key.to_string()))?;
+    if !registration.ability {
+      return Err(AttributeError::NotAnAbility(key.to_string()));
+    }
+
+    Ok((registration, self.resolve(registration, args)?))
+  }
+
+  /// Constructs attribute onto a given [Entity]. Used during level load (see
+  /// [crate::level::load::load_level]). Nothing is inserted if the attribute
+  /// is invalid.
+  pub fn build(
+    &self,
+    attribute: &str,
+    sprite: &str,
+    commands: &mut Commands,
+    target: Entity,
+    level: LevelId,
+    position: Vec2,
+  ) -> Result<(), AttributeBuildError> {
+    self
+      .lookup(attribute)
+      .and_then(|(registration, args)| {
+        let params = self.resolve(registration, args)?;
+        (registration.build)(commands, target, level, position, params)
+      })
+      .map_err(|error| AttributeBuildError {
+        level,
+        sprite: sprite.to_string(),
+        attribute: attribute.to_string(),
+        error,
+      })
+  }
+
+  /// Checks an attribute string against its attribute's schema without
+  /// building it.
+  pub fn validate(&self, attribute: &str) -> Result<(), AttributeError> {
+    let (registration, args) = self.lookup(attribute)?;
+    self.resolve(registration, args).map(|_| ())
+  }
+}
+
+/// Extension to register attributes on an [App] while building plugins.
+pub trait RegisterAttribute {
+  fn register_attribute<A: Attribute>(&mut self) -> &mut Self;
+}
+
+impl RegisterAttribute for App {
+  fn register_attribute<A: Attribute>(&mut self) -> &mut Self {
+    self
+      .world
+      .get_resource_or_insert_with(AttributeRegistry::default)
+      .register::<A>();
+    self
+  }
+}
+
+/// [Plugin] that registers Temple's built in attributes. Required in both play
+/// and edit mode.
+pub struct AttributeRegistryPlugin;
+
+impl Plugin for AttributeRegistryPlugin {
+  fn build(&self, app: &mut App) {
+    app
+      .init_resource::<AttributeRegistry>()
+      .register_attribute::<Player>()
+      .register_attribute::<Solid>()
+      .register_attribute::<Semisolid>()
+      .register_attribute::<Conveyor>()
+      .register_attribute::<Friction>()
+      .register_attribute::<MovingSprite>()
+      .register_attribute::<PathSprite>()
+      .register_attribute::<GroupControl>()
+      .register_attribute::<Crumble>()
+      .register_attribute::<Falling>()
+      .register_attribute::<Bounce>()
+      .register_attribute::<ForceZone>()
+      .register_attribute::<GravityZone>()
+      .register_attribute::<FlipGravity>()
+      .register_attribute::<Water>()
+      .register_attribute::<CameraZone>()
+      .register_attribute::<Switch>()
+      .register_attribute::<Plate>()
+      .register_attribute::<TimerSwitch>()
+      .register_attribute::<Door>()
+      .register_attribute::<Deadly>()
+      .register_attribute::<Pulse>()
+      .register_attribute::<Enemy>()
+      .register_attribute::<Shooter>()
+      .register_attribute::<Checkpoint>()
+      .register_attribute::<Transition>()
+      .register_attribute::<Goal>()
+      .register_attribute::<Collect>()
+      .register_attribute::<Sign>()
+      .register_attribute::<Dialogue>()
+      .register_attribute::<Focus>()
+      .register_attribute::<MusicTrigger>()
+      .register_attribute::<Ambience>()
+      .register_attribute::<Key>()
+      .register_attribute::<Lock>()
+      .register_attribute::<Dash>()
+      .register_attribute::<Flip>()
+      .register_attribute::<Refill>()
+      .register_attribute::<GivableAttribute>()
+      .register_attribute::<TakeableAttribute>();
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use bevy::prelude::*;
+
+  use crate::game::attributes::*;
+  use crate::level::LevelId;
+
+  #[test]
+  fn test_attribute_registry() {
+    let mut app = App::new();
+    app.add_plugin(AttributeRegistryPlugin);
+
+    let registry = app.world.get_resource::<AttributeRegistry>().unwrap();
+    assert!(registry.get(Solid::KEY).is_some());
+    assert!(registry.validate("goal(0)").is_ok());
+    assert!(registry.validate("moving(dir=up, distance=2, dur=4)").is_ok());
+    assert_eq!(
+      registry.validate("bouncy"),
+      Err(AttributeError::UnknownAttribute("bouncy".to_string()))
+    );
+    assert_eq!(
+      registry.validate("goal()"),
+      Err(AttributeError::MissingArgument("exit"))
+    );
+
+    let goal = registry.get(Goal::KEY).unwrap();
+    assert_eq!(goal.signature(), "goal(exit: whole number)");
+    assert_eq!(goal.template(), "goal(exit=0)");
+    assert!(registry.validate(&goal.template()).is_ok());
+
+    let path = registry.get(PathSprite::KEY).unwrap();
+    assert_eq!(path.template(), "path(points=p(x=0.0, y=0.0))");
+    assert!(registry.validate(&path.template()).is_ok());
+  }
+
+  /// Attribute taking only even sizes, to check [Attribute::validate] runs.
+  struct Even;
+
+  impl Attribute for Even {
+    const KEY: &'static str = "even";
+    const PARAMS: &'static [ParamSpec] = &[ParamSpec::required("size", ParamKind::Number)];
+
+    fn build(_: &mut Commands, _: Entity, _: LevelId, _: Vec2, _: AttributeParams) -> Result<(), AttributeError> {
+      Ok(())
+    }
+
+    fn validate(params: &AttributeParams) -> Result<(), AttributeError> {
+      if params.number("size")? % 2 != 0 {
+        return Err(AttributeError::invalid("size", "must be even"));
+      }
+
+      Ok(())
+    }
+  }
+
+  #[test]
+  fn test_attribute_validate() {
+    let mut app = App::new();
+    app.add_plugin(AttributeRegistryPlugin).register_attribute::<Even>();
+
+    let registry = app.world.get_resource::<AttributeRegistry>().unwrap();
+    assert!(registry.validate("even(2)").is_ok());
+    assert!(matches!(
+      registry.validate("even(3)"),
+      Err(AttributeError::InvalidArgument { param: "size", .. })
+    ));
+  }
+
+  #[test]
+  fn test_ability_attributes() {
+    let mut app = App::new();
+    app.add_plugin(AttributeRegistryPlugin);
+
+    let registry = app.world.get_resource::<AttributeRegistry>().unwrap();
+    assert!(registry.validate("give(dash)").is_ok());
+    assert!(registry.validate("give(dash(2))").is_ok());
+    assert!(registry.validate("give(attribute=dash())").is_ok());
+    assert!(registry.validate("take(dash)").is_ok());
+    assert!(registry.validate("give(solid)").is_err());
+    assert!(registry.validate("take(bouncy)").is_err());
+    assert!(registry.validate("give(5)").is_err());
+
+    assert!(registry.resolve_ability(Dash::KEY, vec![]).is_ok());
+    assert_eq!(
+      registry.resolve_ability(Solid::KEY, vec![]).map(|_| ()),
+      Err(AttributeError::NotAnAbility(Solid::KEY.to_string()))
+    );
+  }
+}
@@ -0,0 +1,86 @@
+//! Platform the player can jump up through and land on. Holding `DOWN` while
+//! standing on it drops through. `semisolid()`
+//!
+//! While the player's gravity is flipped, they pass through from above and
+//! land on its underside instead.
+//!
+//! Rather than filtering contacts, the collider is only made solid while the
+//! player is above it, which also hides it from the hover raycast (see
+//! [crate::game::player]) while the player passes through.
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use kurinji::Kurinji;
+
+use super::solid::{set_solid, solid_collider};
+use super::{Attribute, AttributeError, AttributeParams, Player};
+use crate::game::collision_groups::SOLID_GROUP;
+use crate::game::player::PlayerInputCommands;
+use crate::input::DOWN;
+use crate::level::config::SPRITE_SIZE;
+use crate::level::LevelId;
+
+/// Tiles the bottom of the player can sink into the platform while still
+/// counting as above it.
+const LANDING_TOLERANCE: f32 = 0.1;
+
+#[derive(Component)]
+pub struct Semisolid {
+  /// Set while the player drops through, until they are below the platform.
+  dropping: bool,
+}
+
+impl Attribute for Semisolid {
+  const KEY: &'static str = "semisolid";
+
+  fn build(
+    commands: &mut Commands,
+    target: Entity,
+    _: LevelId,
+    position: Vec2,
+    _: AttributeParams,
+  ) -> Result<(), AttributeError> {
+    commands
+      .entity(target)
+      .insert(Semisolid { dropping: false })
+      .insert_bundle(solid_collider(position));
+
+    Ok(())
+  }
+}
+
+/// Makes semisolids solid only while the player is above them, relative to
+/// their gravity, and not dropping through.
+pub fn semisolid_system(
+  input: Res<Kurinji>,
+  player_input_commands: Res<PlayerInputCommands>,
+  player: Query<(&Transform, &Player)>,
+  mut semisolids: Query<(
+    Entity,
+    &mut Semisolid,
+    &ColliderPositionComponent,
+    &mut ColliderFlagsComponent,
+  )>,
+) {
+  if let Ok((trans, player_c)) = player.get_single() {
+    let up = player_c.up();
+    let player_bottom = trans.translation.y / SPRITE_SIZE as f32 - 0.5 * up;
+    let drop = player_input_commands.player_has_input() && input.is_action_active(DOWN);
+
+    semisolids.for_each_mut(|(ent, mut semisolid, position, mut flags)| {
+      let top = position.0.translation.y + 0.5 * up;
+      let above = (player_bottom - top) * up >= -LANDING_TOLERANCE;
+
+      if !above {
+        semisolid.dropping = false;
+      } else if drop && player_c.ground_entity == Some(ent) {
+        semisolid.dropping = true;
+      }
+
+      let solid = above && !semisolid.dropping;
+      if (flags.collision_groups == SOLID_GROUP) != solid {
+        set_solid(&mut flags, solid);
+      }
+    });
+  }
+}
@@ -0,0 +1,199 @@
+//! Fires deadly projectiles on a timer. `shooter(dir, optional period, optional
+//! speed, optional texture)`
+//!
+//! # Usage
+//! `dir`: Direction projectiles are fired (left, right, down, up)
+//!
+//! `period`: Seconds between shots (default 2)
+//!
+//! `speed`: Tiles per second projectiles travel (default 8)
+//!
+//! `texture`: Texture of the projectiles. If not given, projectiles are a
+//! smaller copy of the shooter.
+//!
+//! Projectiles are destroyed when they hit a solid sprite or leave the level.
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use super::{Attribute, AttributeError, AttributeParams, Deadly, MovingDirection, ParamKind, ParamSpec};
+use crate::game::collision::ContactSubscription;
+use crate::game::collision_groups::*;
+use crate::game::physics::PhysicsCommands;
+use crate::level::config::SPRITE_SIZE;
+use crate::level::load::{LevelBounds, LevelLoadComplete, LevelLoadedSprite};
+use crate::level::util::get_texture_path;
+use crate::level::LevelId;
+
+/// Radius of projectiles, in tiles.
+const PROJECTILE_RADIUS: f32 = 0.2;
+/// Tiles outside the level bounds a projectile may travel before it is
+/// destroyed.
+const BOUNDS_MARGIN: f32 = 2.0;
+
+#[derive(Component)]
+pub struct Shooter {
+  pub direction: Vec2,
+  pub period: f32,
+  pub speed: f32,
+  pub texture: Option<String>,
+  position: Vec2,
+  /// Seconds until the next shot.
+  cooldown: f32,
+}
+
+#[derive(Component)]
+pub struct Projectile {
+  /// Tiles per second.
+  pub velocity: Vec2,
+}
+
+impl Attribute for Shooter {
+  const KEY: &'static str = "shooter";
+  const PARAMS: &'static [ParamSpec] = &[
+    ParamSpec::required("dir", ParamKind::Str),
+    ParamSpec::float("period", 2.0),
+    ParamSpec::float("speed", 8.0),
+    ParamSpec::optional("texture", ParamKind::Str),
+  ];
+
+  fn build(
+    commands: &mut Commands,
+    target: Entity,
+    _: LevelId,
+    position: Vec2,
+    params: AttributeParams,
+  ) -> Result<(), AttributeError> {
+    let direction = MovingDirection::from_param(params.str("dir")?)
+      .ok_or_else(|| AttributeError::invalid("dir", "must be one of left, right, up or down"))?;
+    let period = params.float("period")?;
+    let speed = params.float("speed")?;
+    let texture = if params.is_set("texture") {
+      Some(params.str("texture")?.to_string())
+    } else {
+      None
+    };
+
+    if period <= 0.0 {
+      return Err(AttributeError::invalid("period", "must be greater than zero"));
+    }
+    if speed <= 0.0 {
+      return Err(AttributeError::invalid("speed", "must be greater than zero"));
+    }
+
+    commands.entity(target).insert(Shooter {
+      direction: direction.into(),
+      period,
+      speed,
+      texture,
+      position,
+      cooldown: period,
+    });
+
+    Ok(())
+  }
+}
+
+/// Fires projectiles from shooters as their timers run out.
+pub fn shooter_system(
+  mut commands: Commands,
+  time: Res<Time>,
+  physics_commands: Res<PhysicsCommands>,
+  asset_server: Res<AssetServer>,
+  mut shooters: Query<(&mut Shooter, &Handle<Image>)>,
+) {
+  if physics_commands.paused() {
+    return;
+  }
+
+  shooters.for_each_mut(|(mut shooter, shooter_texture)| {
+    shooter.cooldown -= time.delta_seconds();
+    if shooter.cooldown > 0.0 {
+      return;
+    }
+    shooter.cooldown += shooter.period;
+
+    // Start just outside the shooter, so it can be solid
+    let position = shooter.position + shooter.direction * (0.5 + PROJECTILE_RADIUS);
+    let (texture, scale) = match &shooter.texture {
+      Some(texture) => (asset_server.load(get_texture_path(texture)), 1.0),
+      None => (shooter_texture.clone(), 0.5),
+    };
+
+    let collider = ColliderBundle {
+      position: position.into(),
+      shape: ColliderShape::ball(PROJECTILE_RADIUS).into(),
+      material: ColliderMaterialComponent::default(),
+      flags: ColliderFlags {
+        collision_groups: DEADLY_GROUP,
+        solver_groups: NONE_GROUP,
+        active_events: ActiveEvents::CONTACT_EVENTS,
+        ..Default::default()
+      }
+      .into(),
+      ..Default::default()
+    };
+
+    commands
+      .spawn_bundle(SpriteBundle {
+        texture,
+        transform: Transform::from_translation((position * SPRITE_SIZE as f32).extend(0.0))
+          .with_scale(Vec3::splat(scale)),
+        ..Default::default()
+      })
+      .insert(Projectile {
+        velocity: shooter.direction * shooter.speed,
+      })
+      .insert(Deadly)
+      .insert(ContactSubscription)
+      .insert_bundle(collider)
+      .insert(ColliderPositionSync::Discrete)
+      .insert(LevelLoadedSprite);
+  });
+}
+
+/// Moves projectiles, destroying those that hit a solid sprite or leave the
+/// level.
+pub fn projectile_system(
+  mut commands: Commands,
+  time: Res<Time>,
+  physics_commands: Res<PhysicsCommands>,
+  query_pipeline: Res<QueryPipeline>,
+  collider_query: QueryPipelineColliderComponentsQuery,
+  bounds: Query<&LevelBounds, With<LevelLoadComplete>>,
+  mut projectiles: Query<(Entity, &Projectile, &mut ColliderPositionComponent)>,
+) {
+  if physics_commands.paused() {
+    return;
+  }
+
+  let collider_set = QueryPipelineColliderComponentsSet(&collider_query);
+  let bounds = bounds.get_single().ok();
+  let delta_t = time.delta_seconds();
+
+  projectiles.for_each_mut(|(ent, projectile, mut collider_position)| {
+    let translation = collider_position.0.translation;
+    let position = Vec2::new(translation.x, translation.y);
+    let step = projectile.velocity * delta_t;
+
+    // Solid sprites are found the same way as the player's hover
+    let ray = Ray::new(position.into(), projectile.velocity.normalize_or_zero().into());
+    let hit = query_pipeline.cast_ray(
+      &collider_set,
+      &ray,
+      step.length() + PROJECTILE_RADIUS,
+      true,
+      PLAYER_HOVER_GROUP,
+      None,
+    );
+
+    let next = position + step;
+    let out_of_bounds = bounds.map_or(false, |bounds| !bounds.contains(next, BOUNDS_MARGIN));
+
+    if hit.is_some() || out_of_bounds {
+      commands.entity(ent).despawn();
+    } else {
+      collider_position.0 = next.into();
+    }
+  });
+}
@@ -0,0 +1,33 @@
+//! Text shown while the player overlaps the sprite. `sign(text)`
+//!
+//! # Usage
+//! `text`: Quoted text to show, e.g. `sign("Press SPACE to jump")`
+
+use bevy::prelude::*;
+
+use super::zone::zone_collider;
+use super::{Attribute, AttributeError, AttributeParams, ParamKind, ParamSpec};
+use crate::level::LevelId;
+
+#[derive(Component)]
+pub struct Sign(pub String);
+
+impl Attribute for Sign {
+  const KEY: &'static str = "sign";
+  const PARAMS: &'static [ParamSpec] = &[ParamSpec::required("text", ParamKind::Str)];
+
+  fn build(
+    commands: &mut Commands,
+    target: Entity,
+    _: LevelId,
+    position: Vec2,
+    params: AttributeParams,
+  ) -> Result<(), AttributeError> {
+    commands
+      .entity(target)
+      .insert(Sign(params.str("text")?.to_string()))
+      .insert_bundle(zone_collider(position));
+
+    Ok(())
+  }
+}
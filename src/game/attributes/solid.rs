@@ -3,31 +3,51 @@
 use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
 
-use super::lex::ParseArgumentItem;
-use super::Attribute;
+use super::{Attribute, AttributeError, AttributeParams};
 use crate::game::collision_groups::*;
 use crate::level::LevelId;
 
 #[derive(Component)]
 pub struct Solid;
 
+/// Collider for sprites the player can stand on.
+pub(super) fn solid_collider(position: Vec2) -> ColliderBundle {
+  ColliderBundle {
+    position: position.into(),
+    shape: ColliderShape::cuboid(0.5, 0.5).into(),
+    material: ColliderMaterialComponent::default(),
+    flags: ColliderFlags {
+      collision_groups: SOLID_GROUP,
+      solver_groups: SOLID_GROUP,
+      ..Default::default()
+    }
+    .into(),
+    ..Default::default()
+  }
+}
+
+/// Enables or disables a solid collider, for sprites that break or vanish.
+pub fn set_solid(flags: &mut ColliderFlagsComponent, solid: bool) {
+  let groups = if solid { SOLID_GROUP } else { NONE_GROUP };
+  flags.collision_groups = groups;
+  flags.solver_groups = groups;
+}
+
 impl Attribute for Solid {
   const KEY: &'static str = "solid";
 
-  fn build(commands: &mut Commands, target: Entity, _: LevelId, position: Vec2, _: Vec<ParseArgumentItem>) {
-    let collider = ColliderBundle {
-      position: position.into(),
-      shape: ColliderShape::cuboid(0.5, 0.5).into(),
-      material: ColliderMaterialComponent::default(),
-      flags: ColliderFlags {
-        collision_groups: SOLID_GROUP,
-        solver_groups: SOLID_GROUP,
-        ..Default::default()
-      }
-      .into(),
-      ..Default::default()
-    };
+  fn build(
+    commands: &mut Commands,
+    target: Entity,
+    _: LevelId,
+    position: Vec2,
+    _: AttributeParams,
+  ) -> Result<(), AttributeError> {
+    commands
+      .entity(target)
+      .insert(Solid)
+      .insert_bundle(solid_collider(position));
 
-    commands.entity(target).insert(Solid).insert_bundle(collider);
+    Ok(())
   }
 }
@@ -0,0 +1,60 @@
+//! Toggles a signal channel when the player touches it. `switch(channel)`
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use super::give::pickup_collider;
+use super::{Attribute, AttributeError, AttributeParams, ParamKind, ParamSpec};
+use crate::game::collision::{ContactQuery, ContactSubscription, PlayerContacted};
+use crate::game::signal::{SignalChannel, SignalCommands, Signals};
+use crate::level::LevelId;
+
+#[derive(Component)]
+pub struct Switch(pub SignalChannel);
+
+impl Attribute for Switch {
+  const KEY: &'static str = "switch";
+  const PARAMS: &'static [ParamSpec] = &[ParamSpec::required("channel", ParamKind::Number)];
+
+  fn build(
+    commands: &mut Commands,
+    target: Entity,
+    _: LevelId,
+    position: Vec2,
+    params: AttributeParams,
+  ) -> Result<(), AttributeError> {
+    let channel = params.unsigned("channel")?;
+
+    commands
+      .entity(target)
+      .insert(Switch(channel))
+      .insert(ContactSubscription)
+      .insert_bundle(pickup_collider(position))
+      .insert(ColliderPositionSync::Discrete);
+
+    Ok(())
+  }
+}
+
+/// Consumes [PlayerContacted] tags and toggles the switch's channel.
+pub fn on_switch_system(
+  mut commands: Commands,
+  switch_contacted: ContactQuery<Switch>,
+  mut signal_commands: ResMut<SignalCommands>,
+) {
+  switch_contacted.for_each(|(ent, switch)| {
+    signal_commands.toggle(switch.0);
+    commands.entity(ent).remove::<PlayerContacted>();
+  });
+}
+
+/// Flips switches to show the state of their channel.
+pub fn switch_sprite_system(signals: Res<Signals>, mut switches: Query<(&Switch, &mut Sprite)>) {
+  if !signals.is_changed() {
+    return;
+  }
+
+  switches.for_each_mut(|(switch, mut sprite)| {
+    sprite.flip_x = signals.active(switch.0);
+  });
+}
@@ -0,0 +1,57 @@
+//! Takes an ability away from the player. `take(attribute)`
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use super::give::pickup_collider;
+use super::{Attribute, AttributeError, AttributeParams, AttributeRegistry, ParamKind, ParamSpec, Player};
+use crate::game::collision::{ContactQuery, ContactSubscription};
+use crate::level::LevelId;
+
+/// Ability to remove from the player when contacted.
+#[derive(Component)]
+pub struct Take(String);
+
+/// Typeless Take attribute, used to build [Take]
+pub struct TakeableAttribute;
+
+impl Attribute for TakeableAttribute {
+  const KEY: &'static str = "take";
+  const PARAMS: &'static [ParamSpec] = &[ParamSpec::required("attribute", ParamKind::Ability)];
+
+  fn build(
+    commands: &mut Commands,
+    target: Entity,
+    _: LevelId,
+    position: Vec2,
+    params: AttributeParams,
+  ) -> Result<(), AttributeError> {
+    let (key, _) = params.ability("attribute")?;
+
+    commands
+      .entity(target)
+      .insert(Take(key.to_string()))
+      .insert(ContactSubscription)
+      .insert_bundle(pickup_collider(position))
+      .insert(ColliderPositionSync::Discrete);
+
+    Ok(())
+  }
+}
+
+pub fn on_take_system(
+  mut commands: Commands,
+  player: Query<Entity, With<Player>>,
+  take: ContactQuery<Take>,
+  registry: Res<AttributeRegistry>,
+) {
+  if let Ok(player) = player.get_single() {
+    take.for_each(|(ent, take)| {
+      if let Some(registration) = registry.get(&take.0) {
+        (registration.remove)(&mut commands, player);
+      }
+
+      commands.entity(ent).despawn();
+    });
+  }
+}
@@ -0,0 +1,92 @@
+//! Activates a signal channel for a number of seconds when the player touches
+//! it. `timer_switch(channel, secs)`
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use super::give::pickup_collider;
+use super::{Attribute, AttributeError, AttributeParams, ParamKind, ParamSpec};
+use crate::game::collision::{ContactSubscription, PlayerContacted};
+use crate::game::physics::PhysicsCommands;
+use crate::game::signal::{SignalChannel, SignalCommands, Signals};
+use crate::level::LevelId;
+
+#[derive(Component)]
+pub struct TimerSwitch {
+  pub channel: SignalChannel,
+  pub duration: f32,
+  /// Seconds left until the channel is deactivated, and the [Signals]
+  /// generation it was activated in.
+  remaining: Option<(f32, u32)>,
+}
+
+impl Attribute for TimerSwitch {
+  const KEY: &'static str = "timer_switch";
+  const PARAMS: &'static [ParamSpec] = &[
+    ParamSpec::required("channel", ParamKind::Number),
+    ParamSpec::required("secs", ParamKind::Float),
+  ];
+
+  fn build(
+    commands: &mut Commands,
+    target: Entity,
+    _: LevelId,
+    position: Vec2,
+    params: AttributeParams,
+  ) -> Result<(), AttributeError> {
+    let channel = params.unsigned("channel")?;
+    let duration = params.float("secs")?;
+
+    if duration <= 0.0 {
+      return Err(AttributeError::invalid("secs", "must be greater than zero"));
+    }
+
+    commands
+      .entity(target)
+      .insert(TimerSwitch {
+        channel,
+        duration,
+        remaining: None,
+      })
+      .insert(ContactSubscription)
+      .insert_bundle(pickup_collider(position))
+      .insert(ColliderPositionSync::Discrete);
+
+    Ok(())
+  }
+}
+
+/// Consumes [PlayerContacted] tags to start timer switches, and deactivates
+/// their channel once time runs out. Touching a running switch restarts it.
+pub fn timer_switch_system(
+  mut commands: Commands,
+  time: Res<Time>,
+  physics_commands: Res<PhysicsCommands>,
+  signals: Res<Signals>,
+  mut switches: Query<(Entity, &mut TimerSwitch, Option<&PlayerContacted>)>,
+  mut signal_commands: ResMut<SignalCommands>,
+) {
+  switches.for_each_mut(|(ent, mut switch, contacted)| {
+    if contacted.is_some() {
+      switch.remaining = Some((switch.duration, signals.generation()));
+      signal_commands.set(switch.channel, true);
+      commands.entity(ent).remove::<PlayerContacted>();
+      return;
+    }
+
+    if let Some((remaining, generation)) = switch.remaining {
+      // Channels were restored or cleared since the switch was started
+      if generation != signals.generation() {
+        switch.remaining = None;
+      } else if !physics_commands.paused() {
+        let remaining = remaining - time.delta_seconds();
+        if remaining <= 0.0 {
+          switch.remaining = None;
+          signal_commands.set(switch.channel, false);
+        } else {
+          switch.remaining = Some((remaining, generation));
+        }
+      }
+    }
+  });
+}
@@ -3,8 +3,7 @@
 use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
 
-use super::lex::ParseArgumentItem;
-use super::Attribute;
+use super::{Attribute, AttributeError, AttributeParams, ParamKind, ParamSpec};
 use crate::game::collision::{ContactQuery, ContactSubscription, PlayerContacted};
 use crate::game::collision_groups::*;
 use crate::level::load::TransitionLevel;
@@ -13,31 +12,18 @@ use crate::level::LevelId;
 #[derive(Component)]
 pub struct Transition(pub LevelId);
 
-fn parse_params<'a>(params: Vec<ParseArgumentItem>) -> Result<LevelId, &'a str> {
-  if let Some(item) = params.get(0) {
-    if let ParseArgumentItem::Number(id) = item {
-      if let Ok(id) = u32::try_from(*id) {
-        Ok(id)
-      } else {
-        Err("Argument provided must be a positive number!")
-      }
-    } else {
-      Err("Argument provided for Tranistion id was not a number!")
-    }
-  } else {
-    Err("No Argument was Provided to Transition Attribute!")
-  }
-}
-
 impl Attribute for Transition {
   const KEY: &'static str = "trans";
+  const PARAMS: &'static [ParamSpec] = &[ParamSpec::required("level", ParamKind::Number)];
 
-  fn build(commands: &mut Commands, target: Entity, _: LevelId, position: Vec2, params: Vec<ParseArgumentItem>) {
-    let id = parse_params(params);
-
-    if let Err(err) = id {
-      panic!("{}", err);
-    }
+  fn build(
+    commands: &mut Commands,
+    target: Entity,
+    _: LevelId,
+    position: Vec2,
+    params: AttributeParams,
+  ) -> Result<(), AttributeError> {
+    let id = params.unsigned("level")?;
 
     let collider = ColliderBundle {
       position: position.into(),
@@ -58,7 +44,9 @@ impl Attribute for Transition {
       .insert(ContactSubscription)
       .insert_bundle(collider)
       .insert(ColliderPositionSync::Discrete)
-      .insert(Transition(id.unwrap()));
+      .insert(Transition(id));
+
+    Ok(())
   }
 }
 
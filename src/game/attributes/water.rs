@@ -0,0 +1,53 @@
+//! Liquid the player swims through while inside the sprite. `water()`
+//!
+//! Inside water the player is slowed and sinks gently, and each press of
+//! `JUMP` is a swim stroke (see [crate::game::player]). A [Splash] event is
+//! sent whenever the player enters or leaves water.
+
+use bevy::prelude::*;
+
+use super::zone::zone_collider;
+use super::{Attribute, AttributeError, AttributeParams, Player};
+use crate::level::LevelId;
+
+#[derive(Component)]
+pub struct Water;
+
+impl Attribute for Water {
+  const KEY: &'static str = "water";
+
+  fn build(
+    commands: &mut Commands,
+    target: Entity,
+    _: LevelId,
+    position: Vec2,
+    _: AttributeParams,
+  ) -> Result<(), AttributeError> {
+    commands
+      .entity(target)
+      .insert(Water)
+      .insert_bundle(zone_collider(position));
+
+    Ok(())
+  }
+}
+
+/// Event sent when the player enters or leaves water, for effects and sfx.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Splash {
+  Enter,
+  Exit,
+}
+
+/// Sends a [Splash] whenever the player enters or leaves water. The damping of
+/// the player is set by the player controller.
+pub fn water_system(mut was_in_water: Local<bool>, mut splashes: EventWriter<Splash>, player: Query<&Player>) {
+  if let Ok(player_c) = player.get_single() {
+    if player_c.in_water == *was_in_water {
+      return;
+    }
+
+    *was_in_water = player_c.in_water;
+    splashes.send(if player_c.in_water { Splash::Enter } else { Splash::Exit });
+  }
+}
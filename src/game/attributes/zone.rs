@@ -0,0 +1,81 @@
+//! Area sprites that affect the player while overlapping them, such as `force`,
+//! `gravity` and `water`.
+//!
+//! Zones do not use contact events, as they need to know when the player
+//! leaves. Instead, the player's position is tested against [ZONE_GROUP]
+//! colliders every frame. Signs, dialogue and camera zones are found the same
+//! way.
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use super::{ForceZone, GravityZone, Player, Water};
+use crate::game::collision_groups::*;
+use crate::level::config::SPRITE_SIZE;
+
+/// Collider for zone sprites. Never collides, and is only found by
+/// [player_zones].
+pub(super) fn zone_collider(position: Vec2) -> ColliderBundle {
+  ColliderBundle {
+    position: position.into(),
+    shape: ColliderShape::cuboid(0.5, 0.5).into(),
+    material: ColliderMaterialComponent::default(),
+    flags: ColliderFlags {
+      collision_groups: ZONE_GROUP,
+      solver_groups: NONE_GROUP,
+      ..Default::default()
+    }
+    .into(),
+    ..Default::default()
+  }
+}
+
+/// Returns every zone sprite overlapping the point, in tiles.
+pub fn player_zones(
+  query_pipeline: &QueryPipeline,
+  collider_query: &QueryPipelineColliderComponentsQuery,
+  point: Vec2,
+) -> Vec<Entity> {
+  let collider_set = QueryPipelineColliderComponentsSet(collider_query);
+  let mut zones = Vec::new();
+
+  query_pipeline.intersections_with_point(&collider_set, &point.into(), ZONE_GROUP, None, |handle| {
+    zones.push(handle.entity());
+    true
+  });
+
+  zones
+}
+
+/// Sums the modifiers of every zone the player is in onto [Player]. Forces
+/// add together, and gravity scales multiply. The player is in water if any
+/// zone is water.
+pub fn zone_system(
+  query_pipeline: Res<QueryPipeline>,
+  collider_query: QueryPipelineColliderComponentsQuery,
+  force_zones: Query<&ForceZone>,
+  gravity_zones: Query<&GravityZone>,
+  water_zones: Query<(), With<Water>>,
+  mut player: Query<(&Transform, &mut Player)>,
+) {
+  if let Ok((trans, mut player_c)) = player.get_single_mut() {
+    let point = trans.translation.truncate() / SPRITE_SIZE as f32;
+
+    let mut force = Vec2::ZERO;
+    let mut gravity = 1.0;
+    let mut in_water = false;
+    for zone in player_zones(&query_pipeline, &collider_query, point) {
+      if let Ok(force_zone) = force_zones.get(zone) {
+        force += force_zone.0;
+      }
+      if let Ok(gravity_zone) = gravity_zones.get(zone) {
+        gravity *= gravity_zone.0;
+      }
+      in_water |= water_zones.get(zone).is_ok();
+    }
+
+    player_c.zone_force = force;
+    player_c.zone_gravity = gravity;
+    player_c.in_water = in_water;
+  }
+}
@@ -3,13 +3,30 @@
 //! The camera system is designed with cutscene-like target focusing in mind.
 //! Targeting is defined as follows:
 //! - If a [CameraTarget] is defined, it takes camera focus.
-//! - Otherwise, the [Player] is focused.
+//! - Otherwise, the [Player] is focused, framed a little ahead of them away
+//!   from their gravity so flipping gravity also flips the view.
+//!
+//! The camera pans to and from a [CameraTarget], rather than snapping to it
+//! like it does when the player respawns far away.
+//!
+//! The camera's view is kept within the [CameraZone] the player is in, or
+//! otherwise within the level, so empty space past the edge of the map is
+//! never shown. Moving between zones blends from one to the other.
 
 use bevy::prelude::*;
-use bevy::render::camera::Camera;
+use bevy::render::camera::{Camera, OrthographicProjection};
+use bevy_rapier2d::prelude::*;
 
-use crate::game::attributes::Player;
+use crate::game::attributes::{player_zones, CameraZone, Player};
 use crate::level::config::SPRITE_SIZE;
+use crate::level::load::{LevelBounds, LevelLoadComplete};
+
+/// Fraction of the way per second the camera's bounds blend to those of a new
+/// zone.
+const CAMERA_BLEND_RATE: f32 = 4.0;
+
+/// Tiles the camera frames ahead of the player, away from their gravity.
+const PLAYER_LEAD: f32 = 1.0;
 
 /// Tag for a non-player camera focus.
 #[derive(Component)]
@@ -19,6 +36,52 @@ pub struct CameraTarget;
 #[derive(Component)]
 pub struct MainCamera;
 
+/// Rectangle the camera's view is kept within, in pixels.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CameraBounds {
+  pub min: Vec2,
+  pub max: Vec2,
+}
+
+impl CameraBounds {
+  pub fn new(min: Vec2, max: Vec2) -> Self {
+    Self { min, max }
+  }
+
+  /// Bounds covering every tile of the level.
+  pub fn from_level(bounds: &LevelBounds) -> Self {
+    Self::new(
+      (bounds.min - Vec2::splat(0.5)) * SPRITE_SIZE as f32,
+      (bounds.max + Vec2::splat(0.5)) * SPRITE_SIZE as f32,
+    )
+  }
+
+  /// Moves a camera position so its view, extending `half_view` from the
+  /// center, stays within the bounds. The view is centered on the bounds along
+  /// any axis they are smaller than it.
+  pub fn clamp(&self, position: Vec2, half_view: Vec2) -> Vec2 {
+    let low = self.min + half_view;
+    let high = self.max - half_view;
+
+    let clamp_axis = |position: f32, low: f32, high: f32| {
+      if low <= high {
+        position.clamp(low, high)
+      } else {
+        (low + high) / 2.0
+      }
+    };
+
+    Vec2::new(
+      clamp_axis(position.x, low.x, high.x),
+      clamp_axis(position.y, low.y, high.y),
+    )
+  }
+
+  pub fn lerp(&self, other: &Self, t: f32) -> Self {
+    Self::new(self.min.lerp(other.min, t), self.max.lerp(other.max, t))
+  }
+}
+
 /// Component filtering for the camera.
 type CameraOnly = (With<Camera>, With<MainCamera>, Without<Player>);
 
@@ -28,25 +91,76 @@ type CameraTargetOnly = (Without<Camera>, With<CameraTarget>, Without<Player>);
 /// Component filtering for the player.
 type PlayerOnly = (Without<Camera>, Without<CameraTarget>, With<Player>);
 
-/// System to determine camera targeting.
+/// System to determine camera targeting, within the bounds of the camera zone
+/// the player is in or the level.
 fn target_camera(
   time: Res<Time>,
   cam_speed: Res<CameraTrackingSpeed>,
-  mut camera: Query<&mut Transform, CameraOnly>,
+  mut framing: Local<Option<CameraBounds>>,
+  mut panning: Local<bool>,
+  mut lead: Local<f32>,
+  query_pipeline: Res<QueryPipeline>,
+  collider_query: QueryPipelineColliderComponentsQuery,
+  zones: Query<&CameraZone>,
+  level: Query<(&LevelBounds, ChangeTrackers<LevelLoadComplete>)>,
+  mut camera: Query<(&mut Transform, &OrthographicProjection), CameraOnly>,
   targets: Query<&Transform, CameraTargetOnly>,
-  player: Query<&Transform, PlayerOnly>,
+  player: Query<(&Transform, &Player), PlayerOnly>,
 ) {
-  if let Ok(mut camera_trans) = camera.get_single_mut() {
-    let target = if let Ok(target) = targets.get_single() {
+  if let Ok((mut camera_trans, projection)) = camera.get_single_mut() {
+    let focused = targets.get_single().ok();
+    let target = if let Some(target) = focused {
       target.translation.truncate()
-    } else if let Ok(player_trans) = player.get_single() {
-      player_trans.translation.truncate()
+    } else if let Ok((player_trans, player_c)) = player.get_single() {
+      // Ease the lead across when the player's gravity flips, rather than
+      // jumping the view
+      let lead_target = player_c.up() * PLAYER_LEAD * SPRITE_SIZE as f32;
+      *lead += (lead_target - *lead) * (CAMERA_BLEND_RATE * time.delta_seconds()).min(1.0);
+      player_trans.translation.truncate() + Vec2::new(0.0, *lead)
     } else {
       camera_trans.translation.truncate()
     };
 
-    // Snap to target if very far away
-    if target.distance_squared(camera_trans.translation.truncate()) > 1000.0 {
+    // The smallest camera zone the player is in, otherwise the whole level
+    let zone = player
+      .get_single()
+      .ok()
+      .filter(|_| focused.is_none())
+      .and_then(|(player_trans, _)| {
+        let point = player_trans.translation.truncate() / SPRITE_SIZE as f32;
+        player_zones(&query_pipeline, &collider_query, point)
+          .into_iter()
+          .filter_map(|zone| zones.get(zone).ok())
+          .min_by(|a, b| area(&a.bounds).partial_cmp(&area(&b.bounds)).unwrap())
+          .map(|zone| zone.framing())
+      });
+
+    let target = if let Ok((level_bounds, load_tracker)) = level.get_single() {
+      let bounds = zone.unwrap_or_else(|| CameraBounds::from_level(level_bounds));
+      let blended = match *framing {
+        Some(current) if !load_tracker.is_added() => {
+          current.lerp(&bounds, (CAMERA_BLEND_RATE * time.delta_seconds()).min(1.0))
+        },
+        _ => bounds,
+      };
+      *framing = Some(blended);
+
+      let half_view = Vec2::new(projection.right, projection.top) * projection.scale;
+      blended.clamp(target, half_view)
+    } else {
+      target
+    };
+
+    // Snap to target if very far away, unless panning to or back from a
+    // [CameraTarget]
+    let distance_squared = target.distance_squared(camera_trans.translation.truncate());
+    if focused.is_some() {
+      *panning = true;
+    } else if *panning && distance_squared <= 1.0 {
+      *panning = false;
+    }
+
+    if !*panning && distance_squared > 1000.0 {
       camera_trans.translation = Vec3::new(target.x, target.y, camera_trans.translation.z);
       return;
     }
@@ -60,6 +174,11 @@ fn target_camera(
   }
 }
 
+fn area(bounds: &CameraBounds) -> f32 {
+  let size = bounds.max - bounds.min;
+  size.x * size.y
+}
+
 /// [Plugin] for camera systems.
 pub struct CameraPlugin;
 
@@ -73,3 +192,25 @@ impl Plugin for CameraPlugin {
       .add_system(target_camera);
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use bevy::prelude::*;
+
+  use super::*;
+
+  #[test]
+  fn test_camera_bounds_clamp() {
+    let bounds = CameraBounds::new(Vec2::ZERO, Vec2::new(100.0, 40.0));
+    let half_view = Vec2::new(20.0, 30.0);
+
+    // Kept inside along x, and centered along y as the bounds are too short
+    assert_eq!(bounds.clamp(Vec2::new(5.0, 5.0), half_view), Vec2::new(20.0, 20.0));
+    assert_eq!(bounds.clamp(Vec2::new(50.0, 90.0), half_view), Vec2::new(50.0, 20.0));
+    assert_eq!(bounds.clamp(Vec2::new(150.0, 0.0), half_view), Vec2::new(80.0, 20.0));
+
+    // Locked bounds hold the camera at a point
+    let locked = CameraBounds::new(Vec2::splat(10.0), Vec2::splat(10.0));
+    assert_eq!(locked.clamp(Vec2::new(-50.0, 70.0), half_view), Vec2::splat(10.0));
+  }
+}
@@ -7,6 +7,8 @@ use pulldown_cmark::{Event, HeadingLevel, Options, Parser, Tag};
 
 use super::sfx::AudioChannels;
 use crate::input::SELECT;
+use crate::level::{TotalCollectibles, TotalExits};
+use crate::state::game_state::ActiveSave;
 use crate::util::files::{from_game_root, MUSIC_DIR_PATH};
 use crate::util::settings::GameFile;
 
@@ -50,6 +52,9 @@ pub fn load_credits(
   mut credits_data: ResMut<CreditsData>,
   audio: Res<Audio>,
   channels: Res<AudioChannels>,
+  active_save: Res<ActiveSave>,
+  total_exits: Res<TotalExits>,
+  total_collectibles: Res<TotalCollectibles>,
 ) {
   if let Ok(ent) = play_credits.get_single() {
     let music_path = from_game_root(MUSIC_DIR_PATH).join(game_file.credit_music.clone());
@@ -57,7 +62,7 @@ pub fn load_credits(
 
     // Load music
     if asset_server.get_load_state(&music) == LoadState::Loaded {
-      audio.stop_channel(&channels.music.0);
+      channels.stop_music(&audio);
       audio.play_looped_in_channel(
         asset_server.load(music_path.into_os_string().to_str().unwrap()),
         &channels.music.0,
@@ -104,6 +109,25 @@ pub fn load_credits(
       }
     }
 
+    // Completion summary for the active save
+    if let Some(save) = &active_save.0 {
+      credits.push(CreditObject::HLine);
+      credits.push(CreditObject::Header(
+        HeadingLevel::H2,
+        format!("{}'s Completion", save.name),
+      ));
+      credits.push(CreditObject::Text(
+        None,
+        format!("Exits cleared: {}/{}", save.num_cleared_exits(), total_exits.0),
+      ));
+      if total_collectibles.0 > 0 {
+        credits.push(CreditObject::Text(
+          None,
+          format!("Collectibles found: {}/{}", save.num_collected(), total_collectibles.0),
+        ));
+      }
+    }
+
     credits_data.credits_data = credits;
     credits_data.run_credits = true;
 
@@ -0,0 +1,141 @@
+//! Text boxes shown over the game by `sign` and `dialogue` sprites (see
+//! [crate::game::attributes::Sign] and [crate::game::attributes::Dialogue]).
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContext};
+
+use crate::game::player::{InputHold, PlayerInputCommands};
+use crate::level::load::LevelLoadComplete;
+
+/// Pixels between the text box and the bottom of the screen.
+const DIALOGUE_MARGIN: f32 = 24.0;
+
+/// Text box shown over the game. An open dialogue takes priority over the
+/// sign the player is overlapping.
+#[derive(Default)]
+pub struct DialogueBox {
+  /// Pages of the open dialogue, and the page being read.
+  dialogue: Option<(Vec<String>, usize)>,
+  /// Text of the sign the player is overlapping.
+  sign: Option<String>,
+}
+
+impl DialogueBox {
+  /// Opens a dialogue at its first page. Returns false if there are no pages
+  /// to show.
+  pub fn open(&mut self, pages: Vec<String>) -> bool {
+    if pages.is_empty() {
+      return false;
+    }
+
+    self.dialogue = Some((pages, 0));
+    true
+  }
+
+  pub fn is_open(&self) -> bool {
+    self.dialogue.is_some()
+  }
+
+  /// Moves to the next page, closing the dialogue after the last. Returns
+  /// false once closed.
+  pub fn advance(&mut self) -> bool {
+    if let Some((pages, page)) = &mut self.dialogue {
+      *page += 1;
+      if *page < pages.len() {
+        return true;
+      }
+    }
+
+    self.dialogue = None;
+    false
+  }
+
+  pub fn set_sign(&mut self, text: Option<String>) {
+    self.sign = text;
+  }
+
+  /// Text to show, if any.
+  pub fn text(&self) -> Option<&str> {
+    match &self.dialogue {
+      Some((pages, page)) => Some(pages[*page].as_str()),
+      None => self.sign.as_deref(),
+    }
+  }
+
+  /// Page being read and the total number of pages, while a dialogue is open.
+  pub fn page(&self) -> Option<(usize, usize)> {
+    self.dialogue.as_ref().map(|(pages, page)| (page + 1, pages.len()))
+  }
+
+  pub fn clear(&mut self) {
+    self.dialogue = None;
+    self.sign = None;
+  }
+}
+
+/// Closes any text box left from the previous level, returning input held by
+/// an open dialogue.
+fn reset_dialogue_box(
+  mut dialogue_box: ResMut<DialogueBox>,
+  mut player_input_commands: ResMut<PlayerInputCommands>,
+  level_loaded: Query<(), Added<LevelLoadComplete>>,
+) {
+  if level_loaded.iter().next().is_some() {
+    dialogue_box.clear();
+    player_input_commands.grant_input(InputHold::Dialogue);
+  }
+}
+
+/// Draws the [DialogueBox] at the bottom of the screen.
+fn render_dialogue_box(mut egui_ctx: ResMut<EguiContext>, dialogue_box: Res<DialogueBox>) {
+  if let Some(text) = dialogue_box.text() {
+    egui::Window::new("Dialogue")
+      .anchor(egui::Align2::CENTER_BOTTOM, [0.0, -DIALOGUE_MARGIN])
+      .title_bar(false)
+      .resizable(false)
+      .collapsible(false)
+      .show(egui_ctx.ctx_mut(), |ui| {
+        ui.label(text);
+        if let Some((page, pages)) = dialogue_box.page() {
+          ui.small(format!("{}/{} - UP to continue", page, pages));
+        }
+      });
+  }
+}
+
+/// [Plugin] for dialogue text boxes.
+pub struct DialoguePlugin;
+
+impl Plugin for DialoguePlugin {
+  fn build(&self, app: &mut App) {
+    app
+      .init_resource::<DialogueBox>()
+      .add_system(reset_dialogue_box)
+      .add_system(render_dialogue_box);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_dialogue_box() {
+    let mut dialogue_box = DialogueBox::default();
+    assert_eq!(dialogue_box.text(), None);
+    assert!(!dialogue_box.open(Vec::new()));
+
+    // Signs show until a dialogue is opened
+    dialogue_box.set_sign(Some("sign".to_string()));
+    assert_eq!(dialogue_box.text(), Some("sign"));
+
+    assert!(dialogue_box.open(vec!["a".to_string(), "b".to_string()]));
+    assert_eq!(dialogue_box.text(), Some("a"));
+    assert_eq!(dialogue_box.page(), Some((1, 2)));
+    assert!(dialogue_box.advance());
+    assert_eq!(dialogue_box.text(), Some("b"));
+    assert!(!dialogue_box.advance());
+    assert!(!dialogue_box.is_open());
+    assert_eq!(dialogue_box.text(), Some("sign"));
+  }
+}
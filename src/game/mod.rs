@@ -7,8 +7,12 @@ use camera::CameraPlugin;
 use collision::CollisionPlugin;
 use physics::ModifyPhysicsPlugin;
 use player::PlayerPlugin;
+use signal::SignalPlugin;
 
+use self::ai::EnemyAiPlugin;
 use self::credits::CreditsPlugin;
+use self::dialogue::DialoguePlugin;
+use self::music::MusicPlugin;
 use self::orbs::PlayerOrbPlugin;
 use self::sfx::SfxPlugin;
 use crate::level::load::LoadLevel;
@@ -16,14 +20,18 @@ use crate::state::game_state::{ActiveSave, GameMode, GameSaveState, TempleState}
 use crate::ui::overlay::{OverlayCommand, OverlayCommands};
 use crate::util::settings::{GameFile, LevelTransistionType};
 
+pub mod ai;
 pub mod attributes;
 pub mod camera;
 pub mod collision;
 pub mod collision_groups;
 pub mod credits;
+pub mod dialogue;
+pub mod music;
 pub mod physics;
 pub mod player;
 pub mod sfx;
+pub mod signal;
 pub mod orbs;
 
 /// Command to begin the game
@@ -96,11 +104,15 @@ impl PluginGroup for GamePlugins {
       .add(CameraPlugin)
       .add(PlayerPlugin)
       .add(AttributePlugin)
+      .add(EnemyAiPlugin)
       .add(CollisionPlugin)
+      .add(SignalPlugin)
       .add(ModifyPhysicsPlugin)
       .add(BootstrapPlugin)
       .add(SfxPlugin)
       .add(CreditsPlugin)
+      .add(DialoguePlugin)
+      .add(MusicPlugin)
       .add(PlayerOrbPlugin);
   }
 }
@@ -0,0 +1,399 @@
+//! Mid-level music and ambience changes, from `music` and `ambience` sprites
+//! (see [crate::game::attributes::MusicTrigger] and
+//! [crate::game::attributes::Ambience]).
+//!
+//! Each level starts with its manifest's music and no ambience. Changes are
+//! queued with [MusicCommands], and crossfade from the old track to the new one
+//! over two channels. The old track keeps playing until the new one has loaded,
+//! and stays if the new one fails to. The tracks playing when a checkpoint is
+//! reached are restored when the player respawns there, even from another
+//! level.
+
+use std::collections::VecDeque;
+use std::path::Path;
+
+use bevy::asset::LoadState;
+use bevy::prelude::*;
+use bevy_kira_audio::{Audio, AudioChannel, AudioSource};
+
+use super::sfx::AudioChannels;
+use crate::level::load::{KeepMusic, LevelLoadComplete, PreparedLevel, Respawn};
+use crate::util::files::{from_game_root, MUSIC_DIR_PATH};
+
+/// Seconds to crossfade from one track to the next.
+const FADE_SECS: f32 = 1.0;
+
+/// Whether a track exists in the music directory.
+pub fn track_exists(track: &str) -> bool {
+  track_exists_in(&from_game_root(MUSIC_DIR_PATH), track)
+}
+
+fn track_exists_in(dir: &Path, track: &str) -> bool {
+  dir.join(track).is_file()
+}
+
+/// One of the two channels a [TrackFader] crossfades between.
+#[derive(Debug, Default)]
+struct Deck {
+  /// Track the channel should play.
+  track: Option<String>,
+  /// Track the channel is playing.
+  playing: Option<String>,
+  /// Volume multiplier of the channel.
+  level: f32,
+}
+
+impl Deck {
+  fn loaded(&self) -> bool {
+    self.track == self.playing
+  }
+}
+
+/// Crossfades a looping layer between tracks on two channels, fading the old
+/// track out while the new one fades in.
+#[derive(Default)]
+pub struct TrackFader {
+  decks: [Deck; 2],
+  /// Deck of the track being faded to.
+  active: usize,
+}
+
+impl TrackFader {
+  /// Fader for a track already playing at full volume on the first channel.
+  pub fn playing(track: Option<String>) -> Self {
+    Self {
+      decks: [
+        Deck {
+          track: track.clone(),
+          playing: track,
+          level: 1.0,
+        },
+        Deck::default(),
+      ],
+      active: 0,
+    }
+  }
+
+  /// Track playing once any fade is done.
+  pub fn target(&self) -> Option<&String> {
+    self.decks[self.active].track.as_ref()
+  }
+
+  /// Fades to a track, or to silence. A track still fading out fades back in
+  /// from where it is, otherwise the new track takes the other channel.
+  pub fn fade_to(&mut self, track: Option<String>) {
+    if track == self.decks[self.active].track {
+      return;
+    }
+
+    self.active = 1 - self.active;
+    let deck = &mut self.decks[self.active];
+    if deck.track != track {
+      deck.track = track;
+      deck.level = 0.0;
+    }
+  }
+
+  pub fn tick(&mut self, delta: f32) {
+    // The old track plays on at its volume until the new one has loaded
+    if !self.decks[self.active].loaded() {
+      return;
+    }
+
+    let step = delta / FADE_SECS;
+    let active = &mut self.decks[self.active];
+    active.level = (active.level + step).min(1.0);
+    let inactive = &mut self.decks[1 - self.active];
+    inactive.level = (inactive.level - step).max(0.0);
+  }
+
+  /// The track waiting to start, and the channel to start it on.
+  pub fn pending(&self) -> Option<(usize, &String)> {
+    let deck = &self.decks[self.active];
+    match &deck.track {
+      Some(track) if !deck.loaded() => Some((self.active, track)),
+      _ => None,
+    }
+  }
+
+  /// Marks the pending track as playing, to fade in.
+  pub fn started(&mut self) {
+    let deck = &mut self.decks[self.active];
+    deck.playing = deck.track.clone();
+  }
+
+  /// Gives up on the pending track, fading back to the one before it.
+  pub fn failed(&mut self) {
+    self.decks[self.active].track = None;
+    self.active = 1 - self.active;
+  }
+
+  /// A channel to stop, as its track has faded out or been replaced.
+  pub fn finished(&self) -> Option<usize> {
+    (0..2).find(|&i| {
+      let deck = &self.decks[i];
+      deck.playing.is_some() && (!deck.loaded() || (i != self.active && deck.level <= 0.0))
+    })
+  }
+
+  /// Marks a channel as stopped.
+  pub fn stopped(&mut self, deck: usize) {
+    let is_active = deck == self.active;
+    let deck = &mut self.decks[deck];
+    deck.playing = None;
+    if !is_active {
+      deck.track = None;
+    }
+  }
+
+  /// Volume multipliers of the first and second channel.
+  pub fn levels(&self) -> (f32, f32) {
+    (self.decks[0].level, self.decks[1].level)
+  }
+}
+
+enum MusicCommand {
+  Music(String),
+  Ambience(Option<String>),
+  SaveCheckpoint,
+  RestoreCheckpoint,
+}
+
+/// A command queue to change the music and ambience of the level.
+#[derive(Default)]
+pub struct MusicCommands {
+  queue: VecDeque<MusicCommand>,
+}
+
+impl MusicCommands {
+  pub fn play_music(&mut self, track: String) {
+    self.queue.push_back(MusicCommand::Music(track));
+  }
+
+  /// Plays a looping ambience track, or stops ambience for [None].
+  pub fn play_ambience(&mut self, track: Option<String>) {
+    self.queue.push_back(MusicCommand::Ambience(track));
+  }
+
+  /// Saves the tracks playing, to be restored on respawn.
+  pub fn save_checkpoint(&mut self) {
+    self.queue.push_back(MusicCommand::SaveCheckpoint);
+  }
+
+  pub fn restore_checkpoint(&mut self) {
+    self.queue.push_back(MusicCommand::RestoreCheckpoint);
+  }
+
+  fn pop(&mut self) -> Option<MusicCommand> {
+    self.queue.pop_front()
+  }
+}
+
+/// Tracks of the music and ambience channels.
+#[derive(Default)]
+pub struct MusicState {
+  music: TrackFader,
+  ambience: TrackFader,
+  /// Music and ambience playing when the last checkpoint was reached.
+  checkpoint: (Option<String>, Option<String>),
+}
+
+/// Starts each level with its own music and no ambience. The level's music is
+/// played by [crate::level::load::load_level], unless it is kept from the last
+/// level, in which case any music changed by a trigger fades back to it.
+/// Respawning into the level then fades to the tracks of the checkpoint.
+fn reset_music_on_load(
+  audio: Res<Audio>,
+  mut channels: ResMut<AudioChannels>,
+  mut state: ResMut<MusicState>,
+  level: Query<(&PreparedLevel, Option<&KeepMusic>, Option<&Respawn>), Added<LevelLoadComplete>>,
+) {
+  if let Ok((level, keep_music, respawn)) = level.get_single() {
+    audio.stop_channel(&channels.ambience.0);
+    audio.stop_channel(&channels.ambience_crossfade);
+
+    let music = Some(level.0.music.clone());
+    if keep_music.is_some() {
+      state.music.fade_to(music.clone());
+    } else {
+      // The level's music was started on the first channel
+      audio.stop_channel(&channels.music_crossfade);
+      state.music = TrackFader::playing(music.clone());
+    }
+    state.ambience = TrackFader::default();
+
+    if respawn.is_some() {
+      let (music, ambience) = state.checkpoint.clone();
+      state.music.fade_to(music);
+      state.ambience.fade_to(ambience);
+    } else {
+      state.checkpoint = (music, None);
+    }
+
+    channels.music_fade = state.music.levels();
+    channels.ambience_fade = state.ambience.levels();
+  }
+}
+
+/// Consumes [MusicCommands].
+fn handle_music_commands(mut music_commands: ResMut<MusicCommands>, mut state: ResMut<MusicState>) {
+  while let Some(command) = music_commands.pop() {
+    match command {
+      MusicCommand::Music(track) => state.music.fade_to(Some(track)),
+      MusicCommand::Ambience(track) => state.ambience.fade_to(track),
+      MusicCommand::SaveCheckpoint => {
+        state.checkpoint = (state.music.target().cloned(), state.ambience.target().cloned());
+      },
+      MusicCommand::RestoreCheckpoint => {
+        let (music, ambience) = state.checkpoint.clone();
+        state.music.fade_to(music);
+        state.ambience.fade_to(ambience);
+      },
+    }
+  }
+}
+
+/// Steps a fader, stopping channels it is done with and starting its next
+/// track once loaded. Returns the volume multipliers of the channels.
+fn step_fader(
+  fader: &mut TrackFader,
+  channels: [&AudioChannel; 2],
+  delta: f32,
+  audio: &Audio,
+  asset_server: &AssetServer,
+) -> (f32, f32) {
+  fader.tick(delta);
+
+  while let Some(deck) = fader.finished() {
+    audio.stop_channel(channels[deck]);
+    fader.stopped(deck);
+  }
+
+  if let Some((deck, track)) = fader.pending() {
+    let path = from_game_root(MUSIC_DIR_PATH).join(track);
+    let source: Handle<AudioSource> = asset_server.load(path);
+    match asset_server.get_load_state(&source) {
+      LoadState::Loaded => {
+        audio.play_looped_in_channel(source, channels[deck]);
+        fader.started();
+      },
+      LoadState::Failed => {
+        warn!(target: "step_fader", "Unable to load track {}, keeping the previous one.", track);
+        fader.failed();
+      },
+      // Wait for load
+      _ => {},
+    }
+  }
+
+  fader.levels()
+}
+
+/// Crossfades the music and ambience channels between tracks.
+fn music_fade_system(
+  time: Res<Time>,
+  audio: Res<Audio>,
+  asset_server: Res<AssetServer>,
+  mut channels: ResMut<AudioChannels>,
+  mut state: ResMut<MusicState>,
+) {
+  let delta = time.delta_seconds();
+  let state = &mut *state;
+
+  let music_fade = step_fader(
+    &mut state.music,
+    [&channels.music.0, &channels.music_crossfade],
+    delta,
+    &audio,
+    &asset_server,
+  );
+  let ambience_fade = step_fader(
+    &mut state.ambience,
+    [&channels.ambience.0, &channels.ambience_crossfade],
+    delta,
+    &audio,
+    &asset_server,
+  );
+
+  // Only touched when fading, as changes to the channels update their volume
+  if channels.music_fade != music_fade {
+    channels.music_fade = music_fade;
+  }
+  if channels.ambience_fade != ambience_fade {
+    channels.ambience_fade = ambience_fade;
+  }
+}
+
+/// [Plugin] for mid-level music changes.
+pub struct MusicPlugin;
+
+impl Plugin for MusicPlugin {
+  fn build(&self, app: &mut App) {
+    app
+      .init_resource::<MusicCommands>()
+      .init_resource::<MusicState>()
+      .add_system(reset_music_on_load)
+      .add_system(handle_music_commands)
+      .add_system(music_fade_system);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::fs;
+
+  use super::*;
+
+  #[test]
+  fn test_track_exists() {
+    let dir = std::env::temp_dir().join("temple_test_track_exists");
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("cave.ogg"), b"").unwrap();
+
+    assert!(track_exists_in(&dir, "cave.ogg"));
+    assert!(!track_exists_in(&dir, "missing.ogg"));
+    // The directory itself is not a track
+    assert!(!track_exists_in(&dir, ""));
+  }
+
+  #[test]
+  fn test_track_fader() {
+    let mut fader = TrackFader::playing(Some("a.ogg".to_string()));
+
+    // The next track starts on the other channel, and the current one keeps
+    // playing until it has loaded
+    fader.fade_to(Some("b.ogg".to_string()));
+    assert_eq!(fader.target(), Some(&"b.ogg".to_string()));
+    assert_eq!(fader.pending(), Some((1, &"b.ogg".to_string())));
+    fader.tick(FADE_SECS / 2.0);
+    assert_eq!(fader.levels(), (1.0, 0.0));
+
+    // Then the two crossfade, and the old track stops once silent
+    fader.started();
+    assert_eq!(fader.pending(), None);
+    fader.tick(FADE_SECS / 2.0);
+    assert_eq!(fader.levels(), (0.5, 0.5));
+    assert_eq!(fader.finished(), None);
+    fader.tick(FADE_SECS / 2.0);
+    assert_eq!(fader.levels(), (0.0, 1.0));
+    assert_eq!(fader.finished(), Some(0));
+    fader.stopped(0);
+    assert_eq!(fader.finished(), None);
+
+    // A track still fading out fades back in without restarting
+    fader.fade_to(None);
+    fader.tick(FADE_SECS / 2.0);
+    fader.fade_to(Some("b.ogg".to_string()));
+    assert_eq!(fader.pending(), None);
+    fader.tick(FADE_SECS / 2.0);
+    assert_eq!(fader.levels(), (0.0, 1.0));
+
+    // A track that fails to load falls back to the one before
+    fader.fade_to(Some("c.ogg".to_string()));
+    fader.failed();
+    assert_eq!(fader.target(), Some(&"b.ogg".to_string()));
+    assert_eq!(fader.pending(), None);
+    fader.tick(FADE_SECS);
+    assert_eq!(fader.levels(), (0.0, 1.0));
+  }
+}
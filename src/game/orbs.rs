@@ -237,6 +237,11 @@ fn handle_orb_command_queue(
               commands.spawn().insert(PlayerOrb::from_state(OrbState::Dash(active_dash_count + avaliable_orbs + i, DashState::Counter)));
             }
           }
+        } else if count < active_dash_count {
+          // Release surplus dash orbs back to following the player
+          active_orbs.iter_mut().filter(|(_, orb)| matches!(orb.state, OrbState::Dash(id, _) if id >= count)).for_each(|(_, mut orb)| {
+            orb.state = PlayerOrb::default().state;
+          });
         }
       },
       PlayerOrbCommand::UseDashCount => {
@@ -266,19 +271,25 @@ fn handle_orb_command_queue(
   }
 }
 
-fn move_orb_tick(orb_trans: &Vec2, target: &Vec2, orb: &PlayerOrb, time: &Res<Time>) -> Vec2 {
-  let movement_vec = target.clone() - orb_trans.clone();
-  let move_dist = movement_vec.length() / orb.dist;
+/// Eases a follower towards its target, snapping to it when too far behind. Shared with other sprites that trail the
+/// player, such as held keys.
+pub fn follow_tick(trans: &Vec2, target: &Vec2, dist: f32, accel: f32, time: &Res<Time>) -> Vec2 {
+  let movement_vec = target.clone() - trans.clone();
+  let move_dist = movement_vec.length() / dist;
 
   if move_dist > 10.0 {
     return target.clone();
   } else {
-    let accel = orb.accel * (move_dist).min(1.0);
+    let accel = accel * (move_dist).min(1.0);
     let vel = accel * (move_dist).powf(2.0);
-    return orb_trans.clone() + (movement_vec.normalize_or_zero() * vel * time.delta_seconds());
+    return trans.clone() + (movement_vec.normalize_or_zero() * vel * time.delta_seconds());
   }
 }
 
+fn move_orb_tick(orb_trans: &Vec2, target: &Vec2, orb: &PlayerOrb, time: &Res<Time>) -> Vec2 {
+  follow_tick(orb_trans, target, orb.dist, orb.accel, time)
+}
+
 fn handle_orb_state(
   player: Query<&Transform, (With<Player>, Without<PlayerOrb>, Without<DashCrosshair>)>, 
   mut orbs: Query<(&mut PlayerOrb, &mut Transform), (Without<DashCrosshair>, Without<Player>)>, 
@@ -13,6 +13,7 @@ use crate::level::config::SPRITE_SIZE;
 /// (see [super::AttributePlugin])
 #[derive(Debug, Hash, PartialEq, Eq, Clone, SystemLabel)]
 pub enum PlayerSimulationSteps {
+  ApplyZones,
   ApplyMoving,
   ApplyJumping,
 }
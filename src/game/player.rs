@@ -3,7 +3,7 @@
 //! TODO: This plugin should be moved into the same file as the [Player]
 //! attribute.
 
-use std::collections::VecDeque;
+use std::collections::{HashSet, VecDeque};
 
 use bevy::prelude::*;
 use bevy_kira_audio::Audio;
@@ -11,30 +11,38 @@ use bevy_rapier2d::na::Vector2;
 use bevy_rapier2d::prelude::*;
 use kurinji::Kurinji;
 
-use super::attributes::{Dash, DashCrosshair, MovingSprite, Player};
+use super::attributes::{Conveyor, Dash, DashCrosshair, Flip, Friction, PlatformDelta, Player};
 use super::collision_groups::*;
 use super::orbs::PlayerOrbCommands;
 use super::physics::PlayerSimulationSteps;
 use super::sfx::{AudioChannels, SfxHandles};
-use crate::input::{DASH_DOWN, DASH_LEFT, DASH_RIGHT, DASH_UP, DOWN, JUMP, LEFT, RIGHT, SELECT, UP};
+use crate::input::{DASH_DOWN, DASH_LEFT, DASH_RIGHT, DASH_UP, DOWN, FLIP, JUMP, LEFT, RIGHT, SELECT, UP};
 use crate::level::config::SPRITE_SIZE;
 use crate::level::util::get_texture_path;
 
 const PLAYER_MOVE_SPEED: i32 = 15;
 const PLAYER_JUMP_FORCE: u32 = 10;
+const PLAYER_SWIM_FORCE: u32 = 5;
+/// Multiplier of the player's movement force while in water.
+const WATER_MOVE_SCALE: f32 = 0.5;
 
-/// Consumes [Kurinji] inputs for player horizontal movement.
+/// Consumes [Kurinji] inputs for player horizontal movement, adding the force
+/// of any zones the player is in and of the conveyor they are on. Slippery
+/// ground reduces the player's grip.
 fn handle_player_movement(
   input: Res<Kurinji>,
   player_input_commands: Res<PlayerInputCommands>,
-  mut player_force: Query<&mut RigidBodyForcesComponent, With<Player>>,
+  mut player_force: Query<(
+    &Player,
+    &RigidBodyDampingComponent,
+    &RigidBodyMassPropsComponent,
+    &mut RigidBodyForcesComponent,
+  )>,
 ) {
-  if !player_input_commands.player_has_input() {
-    return;
-  }
-
-  if let Some(mut forces) = player_force.iter_mut().next() {
-    let x = if input.is_action_active(RIGHT) {
+  if let Some((player_c, damping, mass_props, mut forces)) = player_force.iter_mut().next() {
+    let x = if !player_input_commands.player_has_input() {
+      0.0
+    } else if input.is_action_active(RIGHT) {
       PLAYER_MOVE_SPEED as f32
     } else if input.is_action_active(LEFT) {
       -PLAYER_MOVE_SPEED as f32
@@ -42,7 +50,16 @@ fn handle_player_movement(
       0.0
     };
 
-    let force: Vector<Real> = Vec2::new(x, 0.0).into();
+    let x = if player_c.in_water {
+      x * WATER_MOVE_SCALE
+    } else {
+      x * player_c.ground_friction.min(1.0)
+    };
+
+    // Conveyors push until the player's damping holds them at the belt's speed
+    let conveyor = player_c.ground_conveyor * damping.linear_damping * mass_props.local_mprops.mass();
+
+    let force: Vector<Real> = (Vec2::new(x + conveyor, 0.0) + player_c.zone_force).into();
     forces.force = force;
   }
 }
@@ -77,11 +94,12 @@ fn handle_height_adjust(
   }
 }
 
-/// Handles player hovering.
+/// Handles player hovering, away from the direction of the player's gravity.
 fn handle_player_hover(
   query_pipeline: Res<QueryPipeline>,
   collider_query: QueryPipelineColliderComponentsQuery,
-  moving_sprite_query: Query<&MovingSprite>,
+  moving_sprite_query: Query<&PlatformDelta>,
+  surface_query: Query<(Option<&Conveyor>, Option<&Friction>)>,
   mut player: Query<(&Transform, &mut Player, &mut RigidBodyVelocityComponent)>,
 ) {
   if let Some((trans, mut player_c, mut vel)) = player.iter_mut().next() {
@@ -91,28 +109,31 @@ fn handle_player_hover(
       trans.translation.x / SPRITE_SIZE as f32,
       trans.translation.y / SPRITE_SIZE as f32,
     );
-    let dir = Vec2::new(0.0, -1.0);
+    let up = player_c.up();
+    let dir = Vec2::new(0.0, -up);
 
     let ray = Ray::new(origin.into(), dir.into());
 
     let impulse_coeff = 5.0;
 
-    let below_ray = Ray::new((origin - Vec2::Y).into(), dir.into());
+    let below_ray = Ray::new((origin + dir).into(), dir.into());
 
     let ground_cast = query_pipeline.cast_ray(&collider_set, &ray, Real::MAX, true, PLAYER_HOVER_GROUP, None);
     let deadly_cast = query_pipeline.cast_ray(&collider_set, &below_ray, 4.0, true, DEADLY_GROUP, None);
 
     if deadly_cast.is_some() && ground_cast.is_none() {
+      player_c.leave_ground();
       return;
     } else if ground_cast.is_some() && deadly_cast.is_some() {
       let (_, g_toi) = ground_cast.unwrap();
       let (_, d_toi) = deadly_cast.unwrap();
       if d_toi <= g_toi {
+        player_c.leave_ground();
         return;
       }
     }
 
-    // Downwards raycast with specific collider group.
+    // Raycast towards gravity with specific collider group.
     if let Some((collided_handle, toi)) = ground_cast {
       let hit_point = ray.point_at(toi);
       let distance_vec = Vec2::new(
@@ -128,13 +149,27 @@ fn handle_player_hover(
           player_c.grounded = true;
         }
 
+        player_c.ground_entity = if player_c.grounded {
+          Some(collided_handle.entity())
+        } else {
+          None
+        };
+
+        // Surface effects of the ground tile
+        let (conveyor, friction) = match player_c.ground_entity.map(|ent| surface_query.get(ent)) {
+          Some(Ok((conveyor, friction))) => (conveyor.map_or(0.0, |c| c.0), friction.map_or(1.0, |f| f.0)),
+          _ => (0.0, 1.0),
+        };
+        player_c.ground_conveyor = conveyor;
+        player_c.ground_friction = friction;
+
         // If on ground, check if on moving platform
         if player_c.grounded {
           if player_c.on_moving_entity.is_none()
             || (player_c.on_moving_entity.is_some() && collided_handle.entity() != player_c.on_moving_entity.unwrap())
           {
             if moving_sprite_query
-              .get_component::<MovingSprite>(collided_handle.entity())
+              .get_component::<PlatformDelta>(collided_handle.entity())
               .is_ok()
             {
               player_c.on_moving_entity = Some(collided_handle.entity());
@@ -152,28 +187,51 @@ fn handle_player_hover(
 
         let imp: Vector2<Real> = Vec2::new(0.0, adjust_i).into();
 
-        vel.linvel.y = vel.linvel.y.max(imp.y);
+        vel.linvel.y = up * (up * vel.linvel.y).max(imp.y);
       } else {
         player_c.outside_ground_bounds = true;
-        player_c.on_moving_entity = None;
+        player_c.leave_ground();
       }
+    } else {
+      player_c.leave_ground();
     }
   }
 }
 
-/// Handles Player Slow Falling. When `JUMP` is actively being held, the player
-/// falls slower.
-fn handle_player_slow_fall(input: Res<Kurinji>, mut player: Query<&mut RigidBodyForcesComponent, With<Player>>) {
-  if let Ok(mut forces) = player.get_single_mut() {
-    if input.is_action_active(JUMP) {
-      forces.gravity_scale = Player::SLOW_FALL_SPEED;
+/// Applies the medium and surface the player is in or on. Water and sticky
+/// ground damp the player more, and slippery ground less.
+fn handle_player_surface(mut player: Query<(&Player, &mut RigidBodyDampingComponent)>) {
+  if let Ok((player_c, mut damping)) = player.get_single_mut() {
+    let linear_damping = if player_c.in_water {
+      Player::WATER_DAMPING
     } else {
-      forces.gravity_scale = Player::NORMAL_FALL_SPEED;
+      Player::AIR_DAMPING * player_c.ground_friction
+    };
+
+    if damping.linear_damping != linear_damping {
+      damping.linear_damping = linear_damping;
     }
   }
 }
 
-/// Consumes [Kurinji] inputs for player jumping.
+/// Handles Player Slow Falling. When `JUMP` is actively being held, the player
+/// falls slower, and in water slower still. Scaled by the gravity of any zones
+/// the player is in, and inverted while the player is flipped.
+fn handle_player_slow_fall(input: Res<Kurinji>, mut player: Query<(&Player, &mut RigidBodyForcesComponent)>) {
+  if let Ok((player_c, mut forces)) = player.get_single_mut() {
+    let fall_speed = if player_c.in_water {
+      Player::WATER_FALL_SPEED
+    } else if input.is_action_active(JUMP) {
+      Player::SLOW_FALL_SPEED
+    } else {
+      Player::NORMAL_FALL_SPEED
+    };
+
+    forces.gravity_scale = fall_speed * player_c.zone_gravity * player_c.up();
+  }
+}
+
+/// Consumes [Kurinji] inputs for player jumping and swimming.
 fn handle_player_jump(
   input: Res<Kurinji>,
   time: Res<Time>,
@@ -188,6 +246,18 @@ fn handle_player_jump(
   }
 
   if let Ok((mut player_c, mut vel)) = player.get_single_mut() {
+    // Swim, with a stroke each time jump is pressed
+    if player_c.in_water {
+      if input.is_action_active(JUMP) && !player_c.jump_in_progress {
+        player_c.jump_in_progress = true;
+        player_c.grounded = false;
+        vel.linvel.y = PLAYER_SWIM_FORCE as f32 * player_c.up();
+      } else if !input.is_action_active(JUMP) {
+        player_c.jump_in_progress = false;
+      }
+      return;
+    }
+
     // Start Jump
     if input.is_action_active(JUMP) && !player_c.jump_in_progress && player_c.grounded {
       player_c.jump_in_progress = true;
@@ -199,7 +269,7 @@ fn handle_player_jump(
     // Apply Forces of the Jump
     if input.is_action_active(JUMP) && player_c.jump_in_progress && !player_c.grounded {
       if player_c.jump_boost_time > 0.0 {
-        vel.linvel.y = PLAYER_JUMP_FORCE as f32;
+        vel.linvel.y = PLAYER_JUMP_FORCE as f32 * player_c.up();
         player_c.jump_boost_time = 0.0_f32.max(player_c.jump_boost_time - time.delta_seconds());
       }
     }
@@ -210,6 +280,31 @@ fn handle_player_jump(
   }
 }
 
+/// Consumes [Kurinji] inputs for the `flip` ability, flipping the player's
+/// gravity once per press while grounded.
+fn handle_player_flip(
+  input: Res<Kurinji>,
+  player_input_commands: Res<PlayerInputCommands>,
+  mut player: Query<(&mut Player, &mut Flip)>,
+) {
+  if let Ok((mut player_c, mut flip)) = player.get_single_mut() {
+    let pressed = player_input_commands.player_has_input() && input.is_action_active(FLIP);
+    if pressed && !flip.pressed && player_c.grounded {
+      player_c.flip();
+    }
+    flip.pressed = pressed;
+  }
+}
+
+/// Turns the player's sprite upside down while their gravity is flipped.
+fn handle_player_orientation(mut player: Query<(&Player, &mut Sprite), Changed<Player>>) {
+  if let Ok((player_c, mut sprite)) = player.get_single_mut() {
+    if sprite.flip_y != player_c.flipped {
+      sprite.flip_y = player_c.flipped;
+    }
+  }
+}
+
 fn handle_dash(
   mut commands: Commands,
   input: Res<Kurinji>,
@@ -220,11 +315,14 @@ fn handle_dash(
   asset_server: Res<AssetServer>,
   mut orb_commands: ResMut<PlayerOrbCommands>,
 ) {
-  if !player_input_commands.player_has_input() {
-    return;
-  }
-
   if let Ok((player, mut vel, mut dash)) = player.get_single_mut() {
+    // Dashes end on time even while input is revoked
+    dash.tick(time.delta_seconds());
+
+    if !player_input_commands.player_has_input() {
+      return;
+    }
+
     if player.grounded {
       if dash.reset_charges() {
         orb_commands.set_dash_count(dash.charges().try_into().unwrap());
@@ -285,51 +383,55 @@ fn handle_dash_crosshair(
   }
 }
 
+/// Holder of a revoke on the player's input. Input is only returned once every
+/// holder has granted it back, so unpausing during a dialogue leaves the
+/// dialogue in control.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum InputHold {
+  Pause,
+  Focus,
+  Dialogue,
+}
+
 enum PlayerInputCommand {
   /// Revokes input from the player
-  RevokeInput,
+  RevokeInput(InputHold),
   /// Grants input to the player
-  GrantInput,
+  GrantInput(InputHold),
   // OverrideInput(Input, Time) (for use in cutscenes)
 }
 
 /// A command queue to handle the revoking, granting, and override of player
 /// inputs. Handled as a queue for the later usage of override input, which may
 /// require the chaining of override input commands for a cutscene.
+#[derive(Default)]
 pub struct PlayerInputCommands {
   queue: VecDeque<PlayerInputCommand>,
-  player_has_input: bool,
+  holds: HashSet<InputHold>,
 }
 
 impl PlayerInputCommands {
-  pub fn revoke_input(&mut self) {
-    self.queue.push_back(PlayerInputCommand::RevokeInput);
+  pub fn revoke_input(&mut self, hold: InputHold) {
+    self.queue.push_back(PlayerInputCommand::RevokeInput(hold));
   }
 
-  pub fn grant_input(&mut self) {
-    self.queue.push_back(PlayerInputCommand::GrantInput);
+  /// Releases a holder's revoke. Granting a holder that holds nothing does
+  /// nothing.
+  pub fn grant_input(&mut self, hold: InputHold) {
+    self.queue.push_back(PlayerInputCommand::GrantInput(hold));
   }
 
   pub fn tick(&mut self) {
     if let Some(command) = self.queue.pop_front() {
       match command {
-        PlayerInputCommand::RevokeInput => self.player_has_input = false,
-        PlayerInputCommand::GrantInput => self.player_has_input = true,
-      }
+        PlayerInputCommand::RevokeInput(hold) => self.holds.insert(hold),
+        PlayerInputCommand::GrantInput(hold) => self.holds.remove(&hold),
+      };
     }
   }
 
   pub fn player_has_input(&self) -> bool {
-    self.player_has_input
-  }
-}
-
-impl Default for PlayerInputCommands {
-  fn default() -> Self {
-    Self {
-      queue: Default::default(),
-      player_has_input: true,
-    }
+    self.holds.is_empty()
   }
 }
 
@@ -344,11 +446,18 @@ impl Plugin for PlayerPlugin {
   fn build(&self, app: &mut App) {
     app
       .init_resource::<PlayerInputCommands>()
-      .add_system(handle_player_movement.label(PlayerSimulationSteps::ApplyMoving))
+      .add_system(
+        handle_player_movement
+          .label(PlayerSimulationSteps::ApplyMoving)
+          .after(PlayerSimulationSteps::ApplyZones),
+      )
       .add_system(handle_player_hover.before(PlayerSimulationSteps::ApplyJumping))
       .add_system(handle_height_adjust)
-      .add_system(handle_player_slow_fall)
+      .add_system(handle_player_surface.after(PlayerSimulationSteps::ApplyZones))
+      .add_system(handle_player_slow_fall.after(PlayerSimulationSteps::ApplyZones))
       .add_system(handle_player_jump.label(PlayerSimulationSteps::ApplyJumping))
+      .add_system(handle_player_flip.before(PlayerSimulationSteps::ApplyJumping))
+      .add_system(handle_player_orientation)
       .add_system(handle_dash)
       .add_system(handle_dash_crosshair)
       .add_system(handle_player_input_commands);
@@ -376,12 +485,35 @@ mod tests {
     assert!(world.get_resource::<PlayerInputCommands>().unwrap().player_has_input());
 
     // Revoke
-    world.get_resource_mut::<PlayerInputCommands>().unwrap().revoke_input();
+    world
+      .get_resource_mut::<PlayerInputCommands>()
+      .unwrap()
+      .revoke_input(InputHold::Pause);
     update_stage.run(&mut world);
     assert!(!world.get_resource::<PlayerInputCommands>().unwrap().player_has_input());
 
     // Grant
-    world.get_resource_mut::<PlayerInputCommands>().unwrap().grant_input();
+    world
+      .get_resource_mut::<PlayerInputCommands>()
+      .unwrap()
+      .grant_input(InputHold::Pause);
+    update_stage.run(&mut world);
+    assert!(world.get_resource::<PlayerInputCommands>().unwrap().player_has_input());
+
+    // Input is held until every holder grants it back
+    let mut input_commands = world.get_resource_mut::<PlayerInputCommands>().unwrap();
+    input_commands.revoke_input(InputHold::Focus);
+    input_commands.revoke_input(InputHold::Pause);
+    input_commands.grant_input(InputHold::Pause);
+    for _ in 0..3 {
+      update_stage.run(&mut world);
+    }
+    assert!(!world.get_resource::<PlayerInputCommands>().unwrap().player_has_input());
+
+    world
+      .get_resource_mut::<PlayerInputCommands>()
+      .unwrap()
+      .grant_input(InputHold::Focus);
     update_stage.run(&mut world);
     assert!(world.get_resource::<PlayerInputCommands>().unwrap().player_has_input());
   }
@@ -1,11 +1,13 @@
 use bevy::prelude::*;
 use bevy_kira_audio::{Audio, AudioChannel, AudioSource};
 
+use super::attributes::Splash;
 use crate::util::files::from_game_root;
 
 /// Jump sound fx path
 const JUMP_SFX: &str = "assets/audio/sfx/jump.wav";
 const CHECKPOINT_SFX: &str = "assets/audio/sfx/checkpoint.wav";
+const SPLASH_SFX: &str = "assets/audio/sfx/splash.wav";
 
 pub struct ChannelState(pub AudioChannel, pub f32);
 
@@ -24,6 +26,25 @@ pub struct AudioChannels {
   pub main_volume: f32,
   pub music: ChannelState,
   pub sfx: ChannelState,
+  /// Looping background layers, such as rain, set by `ambience` sprites.
+  pub ambience: ChannelState,
+  /// Second channels for music and ambience, so a new track can fade in while
+  /// the old one fades out (see [crate::game::music]). Volume is shared with
+  /// the first channel.
+  pub music_crossfade: AudioChannel,
+  pub ambience_crossfade: AudioChannel,
+  /// Volume multipliers of the first and second music and ambience channels
+  /// while crossfading between tracks.
+  pub music_fade: (f32, f32),
+  pub ambience_fade: (f32, f32),
+}
+
+impl AudioChannels {
+  /// Stops the music on both of its channels.
+  pub fn stop_music(&self, audio: &Audio) {
+    audio.stop_channel(&self.music.0);
+    audio.stop_channel(&self.music_crossfade);
+  }
 }
 
 impl Default for AudioChannels {
@@ -32,6 +53,11 @@ impl Default for AudioChannels {
       main_volume: 1.0,
       music: ChannelState::new("music".to_string()),
       sfx: ChannelState::new("sfx".to_string()),
+      ambience: ChannelState::new("ambience".to_string()),
+      music_crossfade: AudioChannel::new("music_crossfade".to_string()),
+      ambience_crossfade: AudioChannel::new("ambience_crossfade".to_string()),
+      music_fade: (1.0, 0.0),
+      ambience_fade: (1.0, 0.0),
     }
   }
 }
@@ -40,23 +66,45 @@ impl Default for AudioChannels {
 pub struct SfxHandles {
   pub jump: Handle<AudioSource>,
   pub checkpoint: Handle<AudioSource>,
+  pub splash: Handle<AudioSource>,
 }
 
 fn load_sfx(asset_server: Res<AssetServer>, mut sfx_handles: ResMut<SfxHandles>) {
   // Load sfxs
   sfx_handles.jump = asset_server.load(from_game_root(JUMP_SFX));
   sfx_handles.checkpoint = asset_server.load(from_game_root(CHECKPOINT_SFX));
+  sfx_handles.splash = asset_server.load(from_game_root(SPLASH_SFX));
 }
 
-fn init_channel_volume(audio: Res<Audio>, channels: Res<AudioChannels>) {
-  audio.set_volume_in_channel(channels.main_volume * channels.music.1, &channels.music.0);
+/// Plays a splash as the player enters or leaves water.
+fn play_splash_sfx(
+  mut splashes: EventReader<Splash>,
+  audio: Res<Audio>,
+  sfx_handles: Res<SfxHandles>,
+  channels: Res<AudioChannels>,
+) {
+  for _ in splashes.iter() {
+    audio.play_in_channel(sfx_handles.splash.clone(), &channels.sfx.0);
+  }
+}
+
+fn set_channel_volumes(audio: &Audio, channels: &AudioChannels) {
+  let music = channels.main_volume * channels.music.1;
+  audio.set_volume_in_channel(music * channels.music_fade.0, &channels.music.0);
+  audio.set_volume_in_channel(music * channels.music_fade.1, &channels.music_crossfade);
   audio.set_volume_in_channel(channels.main_volume * channels.sfx.1, &channels.sfx.0);
+  let ambience = channels.main_volume * channels.ambience.1;
+  audio.set_volume_in_channel(ambience * channels.ambience_fade.0, &channels.ambience.0);
+  audio.set_volume_in_channel(ambience * channels.ambience_fade.1, &channels.ambience_crossfade);
+}
+
+fn init_channel_volume(audio: Res<Audio>, channels: Res<AudioChannels>) {
+  set_channel_volumes(&audio, &channels);
 }
 
 fn update_volumes(audio: Res<Audio>, channels: Res<AudioChannels>) {
   if channels.is_changed() {
-    audio.set_volume_in_channel(channels.main_volume * channels.music.1, &channels.music.0);
-    audio.set_volume_in_channel(channels.main_volume * channels.sfx.1, &channels.sfx.0);
+    set_channel_volumes(&audio, &channels);
   }
 }
 
@@ -68,8 +116,10 @@ impl Plugin for SfxPlugin {
     app
       .init_resource::<AudioChannels>()
       .init_resource::<SfxHandles>()
+      .add_event::<Splash>()
       .add_startup_system(init_channel_volume)
       .add_startup_system(load_sfx)
-      .add_system(update_volumes);
+      .add_system(update_volumes)
+      .add_system(play_splash_sfx);
   }
 }
@@ -0,0 +1,197 @@
+//! Level scoped signal channels, letting one sprite affect another.
+//!
+//! # Usage
+//! Emitter attributes (such as `switch` or `plate`) change a channel through
+//! [SignalCommands], and receiver attributes (such as `door`) read the state
+//! of their channel from [Signals]. Channels are numbered, and start inactive
+//! when a level loads.
+//!
+//! The state of every channel is saved when the player reaches a checkpoint,
+//! and restored when they respawn there.
+
+use std::collections::{HashMap, VecDeque};
+
+use bevy::prelude::*;
+
+use crate::level::load::LevelLoadComplete;
+
+pub type SignalChannel = u32;
+
+/// [Res] of the state of each signal channel in the level.
+#[derive(Default)]
+pub struct Signals {
+  channels: HashMap<SignalChannel, bool>,
+  checkpoint: HashMap<SignalChannel, bool>,
+  generation: u32,
+}
+
+impl Signals {
+  /// Returns true if the channel is active.
+  pub fn active(&self, channel: SignalChannel) -> bool {
+    self.channels.get(&channel).copied().unwrap_or_default()
+  }
+
+  /// Incremented whenever channels are restored or cleared, so emitters with
+  /// their own timers can tell their state has been replaced.
+  pub fn generation(&self) -> u32 {
+    self.generation
+  }
+}
+
+pub enum SignalCommand {
+  Set(SignalChannel, bool),
+  Toggle(SignalChannel),
+  /// Saves all channels, to be restored when the player respawns
+  SaveCheckpoint,
+  /// Restores all channels to when the last checkpoint was reached
+  RestoreCheckpoint,
+  /// Deactivates all channels and forgets the saved checkpoint
+  Clear,
+}
+
+/// A command queue to change [Signals].
+#[derive(Default)]
+pub struct SignalCommands {
+  queue: VecDeque<SignalCommand>,
+}
+
+impl SignalCommands {
+  pub fn set(&mut self, channel: SignalChannel, active: bool) {
+    self.queue.push_back(SignalCommand::Set(channel, active));
+  }
+
+  pub fn toggle(&mut self, channel: SignalChannel) {
+    self.queue.push_back(SignalCommand::Toggle(channel));
+  }
+
+  pub fn save_checkpoint(&mut self) {
+    self.queue.push_back(SignalCommand::SaveCheckpoint);
+  }
+
+  pub fn restore_checkpoint(&mut self) {
+    self.queue.push_back(SignalCommand::RestoreCheckpoint);
+  }
+
+  pub fn clear(&mut self) {
+    self.queue.push_back(SignalCommand::Clear);
+  }
+
+  pub fn pop(&mut self) -> Option<SignalCommand> {
+    self.queue.pop_front()
+  }
+}
+
+/// Consumes all queued [SignalCommands]. Channels are cleared whenever a level
+/// finishes loading.
+pub fn handle_signal_commands(
+  mut signal_commands: ResMut<SignalCommands>,
+  mut signals: ResMut<Signals>,
+  level_loaded: Query<(), Added<LevelLoadComplete>>,
+) {
+  if level_loaded.iter().next().is_some() {
+    signal_commands.clear();
+  }
+
+  while let Some(command) = signal_commands.pop() {
+    match command {
+      SignalCommand::Set(channel, active) => {
+        signals.channels.insert(channel, active);
+      },
+      SignalCommand::Toggle(channel) => {
+        let active = signals.active(channel);
+        signals.channels.insert(channel, !active);
+      },
+      SignalCommand::SaveCheckpoint => {
+        signals.checkpoint = signals.channels.clone();
+      },
+      SignalCommand::RestoreCheckpoint => {
+        signals.channels = signals.checkpoint.clone();
+        signals.generation += 1;
+      },
+      SignalCommand::Clear => {
+        signals.channels.clear();
+        signals.checkpoint.clear();
+        signals.generation += 1;
+      },
+    }
+  }
+}
+
+/// Simulation steps for signals. Emitters run before
+/// [SignalSystemSteps::ApplyCommands], and receivers after.
+#[derive(Debug, Hash, PartialEq, Eq, Clone, SystemLabel)]
+pub enum SignalSystemSteps {
+  ApplyCommands,
+}
+
+/// [Plugin] for level signal channels.
+pub struct SignalPlugin;
+
+impl Plugin for SignalPlugin {
+  fn build(&self, app: &mut App) {
+    app
+      .init_resource::<Signals>()
+      .init_resource::<SignalCommands>()
+      .add_system(handle_signal_commands.label(SignalSystemSteps::ApplyCommands));
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use bevy::prelude::*;
+
+  use super::*;
+
+  #[test]
+  fn test_signal_commands() {
+    let mut world = World::default();
+    let mut update_stage = SystemStage::parallel();
+
+    world.insert_resource(Signals::default());
+    world.insert_resource(SignalCommands::default());
+    update_stage.add_system(handle_signal_commands);
+
+    // Channels start inactive
+
+    update_stage.run(&mut world);
+    assert!(!world.get_resource::<Signals>().unwrap().active(1));
+
+    // Set and toggle
+
+    let mut signal_commands = world.get_resource_mut::<SignalCommands>().unwrap();
+    signal_commands.set(1, true);
+    signal_commands.toggle(2);
+    update_stage.run(&mut world);
+
+    let signals = world.get_resource::<Signals>().unwrap();
+    assert!(signals.active(1));
+    assert!(signals.active(2));
+
+    // Respawning restores the state at the last checkpoint
+
+    let mut signal_commands = world.get_resource_mut::<SignalCommands>().unwrap();
+    signal_commands.save_checkpoint();
+    signal_commands.toggle(1);
+    signal_commands.set(3, true);
+    update_stage.run(&mut world);
+    assert!(!world.get_resource::<Signals>().unwrap().active(1));
+
+    world.get_resource_mut::<SignalCommands>().unwrap().restore_checkpoint();
+    update_stage.run(&mut world);
+
+    let signals = world.get_resource::<Signals>().unwrap();
+    assert!(signals.active(1));
+    assert!(signals.active(2));
+    assert!(!signals.active(3));
+    assert_eq!(signals.generation(), 1);
+
+    // Clear
+
+    world.get_resource_mut::<SignalCommands>().unwrap().clear();
+    update_stage.run(&mut world);
+
+    let signals = world.get_resource::<Signals>().unwrap();
+    assert!(!signals.active(1));
+    assert!(!signals.active(2));
+  }
+}
@@ -18,6 +18,8 @@ pub const LEFT: &str = "LEFT";
 pub const DOWN: &str = "DOWN";
 /// Input key for jump action
 pub const JUMP: &str = "JUMP";
+/// Input key for flipping gravity, with the `flip` ability
+pub const FLIP: &str = "FLIP";
 /// Input key for menu action
 pub const MENU: &str = "MENU";
 /// Input key for edit mode zoom out
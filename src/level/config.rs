@@ -3,7 +3,7 @@
 //! Level manifests are loaded on game boot and
 //! are stored in a Bevy Resource.
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::vec::Vec;
 
 use bevy::prelude::*;
@@ -24,6 +24,9 @@ pub struct LevelManifest {
   /// Sprites used in Level
   #[serde(skip_serializing_if = "Vec::is_empty", default)]
   pub sprites: Vec<LevelSpriteEntry>,
+  /// Scripts read from `dialogue` sprites, as pages of text by id
+  #[serde(skip_serializing_if = "BTreeMap::is_empty", default)]
+  pub dialogue: BTreeMap<String, Vec<String>>,
 }
 
 /// Sprites used in a [Level], defined in a [LevelManifest]
@@ -230,6 +233,25 @@ pub struct Level {
   pub music: String,
   /// Sprites for Level Map
   pub sprites: Vec<HandledSprite>,
+  /// Dialogue scripts, as pages of text by id
+  pub dialogue: BTreeMap<String, Vec<String>>,
+}
+
+impl Level {
+  /// Returns the lower left and upper right corners of the level, in tiles.
+  pub fn bounds(&self) -> (Vec2, Vec2) {
+    if self.sprites.is_empty() {
+      return (Vec2::ZERO, Vec2::ZERO);
+    }
+
+    self
+      .sprites
+      .iter()
+      .fold((Vec2::splat(f32::MAX), Vec2::splat(f32::MIN)), |(min, max), sprite| {
+        let pos = sprite.pos.as_vec2();
+        (min.min(pos), max.max(pos))
+      })
+  }
 }
 
 /// Form Level from Manifest Components
@@ -239,6 +261,7 @@ impl From<(LevelManifest, Vec<HandledSprite>)> for Level {
       name: manifest.name,
       music: manifest.music,
       sprites: map,
+      dialogue: manifest.dialogue,
     }
   }
 }
@@ -251,6 +274,7 @@ impl Into<(LevelManifest, LevelMap)> for Level {
       name: self.name,
       music: self.music,
       sprites: level_sprites,
+      dialogue: self.dialogue,
     };
 
     let map = LevelMap { sprites: map_sprites };
@@ -275,6 +299,7 @@ mod tests {
       name: LEVEL_NAME.to_string(),
       music: "test.ogg".to_string(),
       sprites: vec![HandledSprite::new(SPRITE_NAME, (0, 0), (0, 0), "", vec![ATTR])],
+      dialogue: BTreeMap::from([("intro".to_string(), vec!["Hello".to_string(), "There".to_string()])]),
     };
 
     let level_copy = level.clone();
@@ -287,6 +312,7 @@ mod tests {
     assert_eq!(manifest.name, LEVEL_NAME.to_string());
     assert_eq!(manifest.sprites[0].name, SPRITE_NAME);
     assert_eq!(manifest.sprites[0].attributes[0], ATTR);
+    assert_eq!(manifest.dialogue["intro"].len(), 2);
     assert_eq!(map_file.sprite_types[&0], SPRITE_NAME.to_string());
     assert_eq!(
       map_file.sprite_entries[0],
@@ -302,4 +328,19 @@ mod tests {
     // Verify equal
     assert_eq!(level_copy, new_level);
   }
+
+  #[test]
+  fn test_level_bounds() {
+    let level = Level {
+      name: "bounds".to_string(),
+      music: "test.ogg".to_string(),
+      sprites: vec![
+        HandledSprite::new("a", (-2, 5), (0, 0), "", vec![]),
+        HandledSprite::new("b", (7, -1), (0, 0), "", vec![]),
+      ],
+      dialogue: Default::default(),
+    };
+
+    assert_eq!(level.bounds(), (Vec2::new(-2.0, -1.0), Vec2::new(7.0, 5.0)));
+  }
 }
@@ -28,6 +28,9 @@ use crate::state::game_state::{ActiveSave, GameMode, TempleState};
 use crate::ui::overlay::{OverlayCommand, OverlayCommands};
 use crate::util::files::{from_game_root, MUSIC_DIR_PATH};
 
+/// Tint applied to sprites with an attribute that failed to build.
+const BROKEN_SPRITE_COLOR: Color = Color::rgb(1.0, 0.25, 0.25);
+
 /// Instruction to load a new level
 #[derive(Component)]
 pub struct LoadLevel(pub LevelId);
@@ -40,6 +43,27 @@ pub struct PreparedLevel(pub Level);
 #[derive(Component)]
 pub struct LevelLoadComplete;
 
+/// Extent of the loaded level, in tiles. Added to the same entity as
+/// [LevelLoadComplete].
+#[derive(Component, Clone, Copy, Debug)]
+pub struct LevelBounds {
+  pub min: Vec2,
+  pub max: Vec2,
+}
+
+impl LevelBounds {
+  /// Returns true if the point is within the level, allowing `margin` tiles
+  /// outside it.
+  pub fn contains(&self, point: Vec2, margin: f32) -> bool {
+    point.cmpge(self.min - margin).all() && point.cmple(self.max + margin).all()
+  }
+}
+
+/// Tag that a sprite has an attribute that failed to build. Highlighted by
+/// [highlight_broken_sprites].
+#[derive(Component)]
+pub struct BrokenAttribute;
+
 /// Instruction to unload a level. Must be added to the same entity as
 /// [LoadLevel]
 #[derive(Component)]
@@ -63,6 +87,11 @@ pub struct WaitUntilUnloaded;
 #[derive(Component)]
 pub struct KeepMusic;
 
+/// Instruction marking a transition, and the load it produces, as the player
+/// respawning at a checkpoint in another level.
+#[derive(Component)]
+pub struct Respawn;
+
 /// Instruction to skip to next checkpoint
 #[derive(Component)]
 pub struct NextCheckpoint;
@@ -147,6 +176,7 @@ pub fn load_level(
   channels: Res<AudioChannels>,
   mut overlay_commands: ResMut<OverlayCommands>,
   mut cursor_commands: ResMut<CursorCommands>,
+  registry: Res<AttributeRegistry>,
 ) {
   query.for_each(|(e, load_level, prepared_level)| {
     let level_id = load_level.0;
@@ -207,7 +237,10 @@ pub fn load_level(
         if attribute == Player::KEY {
           player_trans = unit_pos;
         }
-        build_attribute(attribute.clone(), &mut commands, entity, level_id, position);
+        if let Err(err) = registry.build(attribute, &sprite.name, &mut commands, entity, level_id, position) {
+          error!(target: "load_level", "{}", err);
+          commands.entity(entity).insert(BrokenAttribute);
+        }
       }
     }
 
@@ -238,7 +271,19 @@ pub fn load_level(
       cursor_commands.lock_cursor();
       overlay_commands.command(OverlayCommand::FadeOut(1.0));
     }
-    commands.entity(e).insert(LevelLoadComplete);
+    let (min, max) = level.bounds();
+    commands
+      .entity(e)
+      .insert(LevelBounds { min, max })
+      .insert(LevelLoadComplete);
+  });
+}
+
+/// Tints sprites tagged with [BrokenAttribute], keeping the rest of their
+/// [Sprite] intact.
+pub fn highlight_broken_sprites(mut query: Query<&mut Sprite, Added<BrokenAttribute>>) {
+  query.for_each_mut(|mut sprite| {
+    sprite.color = BROKEN_SPRITE_COLOR;
   });
 }
 
@@ -302,12 +347,12 @@ pub fn transition_level(
   mut commands: Commands,
   audio: Res<Audio>,
   channels: Res<AudioChannels>,
-  transition_command: Query<(Entity, &TransitionLevel)>,
+  transition_command: Query<(Entity, &TransitionLevel, Option<&Respawn>)>,
   loaded_level: Query<(Entity, &LoadLevel), With<LevelLoadComplete>>,
   loading_levels: Query<Entity, (With<LoadLevel>, Without<LevelLoadComplete>)>,
 ) {
   let mut transitioned = false;
-  for (entity, trans) in transition_command.iter() {
+  for (entity, trans, respawn) in transition_command.iter() {
     // Run a single transition, prevents multiple transition entities on the same
     // frame.
     if !transitioned {
@@ -321,7 +366,10 @@ pub fn transition_level(
           if levels_have_same_music(old_level.0, trans.0) {
             level_ent.insert(KeepMusic);
           } else {
-            audio.stop_channel(&channels.music.0);
+            channels.stop_music(&audio);
+          }
+          if respawn.is_some() {
+            level_ent.insert(Respawn);
           }
         } else {
           warn!(target: "transition_level", "Unable to get currently loaded level! None or More than 1 Loaded?");
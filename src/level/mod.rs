@@ -1,5 +1,7 @@
 //! Level config management and level loading.
 
+use std::collections::BTreeSet;
+
 use bevy::prelude::*;
 use load::*;
 use verify::verify_level_files;
@@ -7,7 +9,7 @@ use verify::verify_level_files;
 use self::load::{next_checkpoint, wait_until_unloaded};
 use self::next::auto_next_level;
 use self::util::get_level_manifests;
-use crate::game::attributes::{Attribute, Goal};
+use crate::game::attributes::{Attribute, Collect, Goal};
 
 pub mod config;
 pub mod load;
@@ -25,11 +27,14 @@ impl Plugin for LevelPlugin {
   fn build(&self, app: &mut App) {
     app
       .init_resource::<TotalExits>()
+      .init_resource::<TotalCollectibles>()
       .add_startup_system(verify_level_files)
       .add_startup_system(count_exits)
+      .add_startup_system(count_collectibles)
       .add_system(wait_until_unloaded)
       .add_system(prepare_level)
       .add_system(load_level)
+      .add_system(highlight_broken_sprites)
       .add_system(unload_level)
       .add_system(apply_save_on_load)
       .add_system(transition_level)
@@ -60,3 +65,22 @@ fn count_exits(mut exits: ResMut<TotalExits>) {
 
   exits.0 = exit_count;
 }
+
+#[derive(Default)]
+pub struct TotalCollectibles(pub usize);
+
+fn count_collectibles(mut collectibles: ResMut<TotalCollectibles>) {
+  let levels = get_level_manifests();
+  // Sprites sharing a collect id are the same collectible, as in the save
+  let found: BTreeSet<(LevelId, u32)> = levels
+    .iter()
+    .flat_map(|(id, manifest)| {
+      manifest
+        .sprites
+        .iter()
+        .flat_map(|item| item.attributes.iter())
+        .filter_map(move |attribute| Collect::id_of(attribute).map(|collect| (*id, collect)))
+    })
+    .collect();
+  collectibles.0 = found.len();
+}
@@ -78,6 +78,7 @@ mod tests {
         name: NAME.to_string(),
         music: "".to_string(),
         sprites: vec![HandledSprite::new("sprite", (0, 0), (0, 0), "", vec!["solid"])],
+        dialogue: Default::default(),
       }),
       LoadLevel(0),
       LevelLoadComplete,
@@ -1,15 +1,30 @@
 use bevy::prelude::*;
 
 use super::util::{get_level_manifests, get_map_by_id};
+use crate::game::attributes::{AttributeBuildError, AttributeRegistry};
 use crate::state::game_state::TempleState;
 
 /// System that loads all levels into the [LevelMap] resource to warn if any
-/// issues.
-pub fn verify_level_files(temple_state: Res<TempleState>) {
+/// issues, including attributes that fail to validate.
+pub fn verify_level_files(temple_state: Res<TempleState>, registry: Res<AttributeRegistry>) {
   // Load level manifest directory
   let manifests = get_level_manifests();
 
-  for (id, _) in manifests {
+  for (id, manifest) in manifests {
+    for sprite in manifest.sprites.iter() {
+      for attribute in sprite.attributes.iter() {
+        if let Err(error) = registry.validate(attribute) {
+          let err = AttributeBuildError {
+            level: id,
+            sprite: sprite.name.clone(),
+            attribute: attribute.clone(),
+            error,
+          };
+          warn!(target: "verify_level_files", "{}. The attribute will be skipped when the level loads.", err);
+        }
+      }
+    }
+
     let map = get_map_by_id(id);
     // If in play mode, loading a level without a map will crash
     if map.is_none() && !temple_state.in_edit_mode() {
@@ -9,6 +9,7 @@ use bevy_egui::EguiPlugin;
 use bevy_kira_audio::AudioPlugin;
 use bevy_rapier2d::prelude::*;
 use editor::EditorPlugins;
+use game::attributes::AttributeRegistryPlugin;
 use game::physics::PhysicsCommands;
 use game::player::PlayerInputCommands;
 use game::sfx::SfxPlugin;
@@ -81,6 +82,7 @@ fn build_base_app(app: &mut App, game_file: GameFile, cli_args: CliArgs, setting
     // Game Plugins
     .add_plugin(StatePlugin)
     .add_plugin(InputPlugin)
+    .add_plugin(AttributeRegistryPlugin)
     .add_plugin(LevelPlugin)
     .add_plugin(UiPlugin)
     .add_startup_system(handle_cli_args);
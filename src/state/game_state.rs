@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::fs::{create_dir, read, read_dir, write};
 
 use bevy::prelude::*;
@@ -55,11 +55,13 @@ impl TempleState {
 
 pub type CheckpointState = (LevelId, f32, f32);
 
-/// Describes the state of a level save, including exits completed and current
-/// checkpoint.
+/// Describes the state of a level save, including exits completed, collectibles
+/// found and current checkpoint.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct LevelSaveState {
   exits_cleared: Vec<bool>,
+  #[serde(skip_serializing_if = "BTreeSet::is_empty", default)]
+  collected: BTreeSet<u32>,
   checkpoint_state: Option<CheckpointState>,
 }
 
@@ -67,6 +69,7 @@ impl LevelSaveState {
   pub fn new_with_checkpoint(state: CheckpointState) -> Self {
     Self {
       exits_cleared: Vec::new(),
+      collected: BTreeSet::new(),
       checkpoint_state: Some(state),
     }
   }
@@ -109,12 +112,29 @@ impl LevelSaveState {
   pub fn exits_cleared(&self) -> Vec<bool> {
     self.exits_cleared.clone()
   }
+
+  /// Marks a collectible on the level as found. Returns false if it already
+  /// was.
+  pub fn collect(&mut self, id: u32) -> bool {
+    self.collected.insert(id)
+  }
+
+  /// Returns true if the collectible has been found
+  pub fn collected(&self, id: u32) -> bool {
+    self.collected.contains(&id)
+  }
+
+  /// Returns the number of collectibles found on this level
+  pub fn num_collected(&self) -> usize {
+    self.collected.len()
+  }
 }
 
 impl Default for LevelSaveState {
   fn default() -> Self {
     Self {
       exits_cleared: Vec::new(),
+      collected: BTreeSet::new(),
       checkpoint_state: None,
     }
   }
@@ -148,6 +168,10 @@ impl GameSaveState {
       .into_iter()
       .fold(0, |acc, x| acc + x.exits_cleared().iter().filter(|&&x| x).count())
   }
+
+  pub fn num_collected(&self) -> usize {
+    self.level_clears.values().map(LevelSaveState::num_collected).sum()
+  }
 }
 
 #[cfg(not(test))]
@@ -242,6 +266,16 @@ mod tests {
     assert_eq!(save2.checkpoint().unwrap(), (0, 5.0, 10.0));
     save2.set_checkpoint((0, 10.0, 20.0));
     assert_eq!(save2.checkpoint().unwrap(), (0, 10.0, 20.0));
+
+    let mut save3 = LevelSaveState::default();
+    assert!(!save3.collected(2));
+    assert!(save3.collect(2));
+    assert!(!save3.collect(2));
+    assert!(save3.collected(2));
+    assert!(!save3.collected(0));
+    save3.clear_exit(0);
+    assert!(save3.collected(2));
+    assert_eq!(1, save3.num_collected());
   }
 
   #[test]
@@ -284,5 +318,10 @@ mod tests {
     game.level_clears.get_mut(&GameSaveState::key(0)).unwrap().clear_exit(0);
     game.level_clears.get_mut(&GameSaveState::key(0)).unwrap().clear_exit(5);
     assert_eq!(2, game.num_cleared_exits());
+
+    game.level_clears.get_mut(&GameSaveState::key(0)).unwrap().collect(0);
+    game.level_clears.entry(GameSaveState::key(1)).or_default().collect(3);
+    assert_eq!(2, game.num_collected());
+    assert_eq!(2, game.num_cleared_exits());
   }
 }
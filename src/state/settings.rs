@@ -51,16 +51,23 @@ pub struct Settings {
   pub main_mixer: f32,
   pub music_volume: f32,
   pub sfx_volume: f32,
+  #[serde(default = "default_volume")]
+  pub ambience_volume: f32,
   // Display Settings
   pub scale: WindowSize,
 }
 
+fn default_volume() -> f32 {
+  0.7
+}
+
 impl Default for Settings {
   fn default() -> Self {
     Self {
       main_mixer: 1.0,
       music_volume: 0.7,
       sfx_volume: 0.7,
+      ambience_volume: default_volume(),
       scale: WindowSize::Dim1028x900,
     }
   }
@@ -93,6 +100,7 @@ impl Settings {
     self.main_mixer = channels.main_volume;
     self.music_volume = channels.music.1;
     self.sfx_volume = channels.sfx.1;
+    self.ambience_volume = channels.ambience.1;
     self.save();
   }
 }
@@ -4,7 +4,7 @@ use kurinji::Kurinji;
 
 use super::settings::render_settings_menu;
 use crate::game::physics::PhysicsCommands;
-use crate::game::player::PlayerInputCommands;
+use crate::game::player::{InputHold, PlayerInputCommands};
 use crate::game::sfx::AudioChannels;
 use crate::input::{CursorCommands, MENU};
 use crate::level::load::NextCheckpoint;
@@ -38,11 +38,11 @@ pub fn pause_menu_buttons(
     state.action_held_down = true;
     state.menu_active = true;
     cursor_commands.unlock_cursor();
-    player_input_commands.revoke_input();
+    player_input_commands.revoke_input(InputHold::Pause);
     physics_commands.pause();
   } else if (input.is_action_active(MENU) && state.menu_active && !state.action_held_down) || state.force_unpause {
     cursor_commands.lock_cursor();
-    player_input_commands.grant_input();
+    player_input_commands.grant_input(InputHold::Pause);
     physics_commands.resume();
     state.action_held_down = true;
     state.menu_active = false;
@@ -16,6 +16,7 @@ pub fn render_settings_menu(ui: &mut Ui, mut channels: ResMut<AudioChannels>, mu
   ui.add(egui::Slider::new(&mut channels.main_volume, 0.0..=2.0).text("Main Volume"));
   ui.add(egui::Slider::new(&mut channels.music.1, 0.0..=2.0).text("Music Volume"));
   ui.add(egui::Slider::new(&mut channels.sfx.1, 0.0..=2.0).text("SFX Volume"));
+  ui.add(egui::Slider::new(&mut channels.ambience.1, 0.0..=2.0).text("Ambience Volume"));
   if ui.button(format!("Window Size {}", settings.scale)).clicked() {
     settings.scale = cycle_window_dimensions(settings.scale);
     settings.save();
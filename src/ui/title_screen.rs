@@ -9,7 +9,7 @@ use bevy_egui::{egui, EguiContext};
 use super::settings::render_settings_menu;
 use crate::game::sfx::AudioChannels;
 use crate::game::BeginGame;
-use crate::level::TotalExits;
+use crate::level::{TotalCollectibles, TotalExits};
 use crate::state::game_state::{write_saves, ActiveSave, AvaliableSaves, GameSaveState};
 use crate::state::settings::Settings;
 use crate::util::files::from_game_root;
@@ -154,8 +154,9 @@ pub fn title_menu_buttons(
   mut title_menu_state: ResMut<TitleMenuState>,
   mut saves: ResMut<AvaliableSaves>,
   mut active_save: ResMut<ActiveSave>,
-  mut exit_count_cache: Local<HashMap<String, usize>>,
+  mut exit_count_cache: Local<HashMap<String, (usize, usize)>>,
   total_exits: Res<TotalExits>,
+  total_collectibles: Res<TotalCollectibles>,
   channels: ResMut<AudioChannels>,
   settings: ResMut<Settings>,
 ) {
@@ -190,10 +191,18 @@ pub fn title_menu_buttons(
 
             for (name, save) in &saves.0 {
               if !exit_count_cache.contains_key(name) {
-                exit_count_cache.insert(name.to_string(), save.num_cleared_exits());
+                exit_count_cache.insert(name.to_string(), (save.num_cleared_exits(), save.num_collected()));
               }
 
-              let file_fmt = format!("{} {}/{}", name, exit_count_cache[name], total_exits.0);
+              let (cleared_exits, collected) = exit_count_cache[name];
+              let file_fmt = if total_collectibles.0 > 0 {
+                format!(
+                  "{} {}/{}  ({}/{} collected)",
+                  name, cleared_exits, total_exits.0, collected, total_collectibles.0
+                )
+              } else {
+                format!("{} {}/{}", name, cleared_exits, total_exits.0)
+              };
 
               if ui.add_sized([400.0, 35.0], egui::Button::new(file_fmt)).clicked() {
                 active_save.0 = Some(save.clone());